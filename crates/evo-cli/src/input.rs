@@ -0,0 +1,126 @@
+//! Loading jobs and genomes from disk.
+
+use anyhow::{bail, Context, Result};
+use evo_core::{JobConfig, JobId, LineageId, MapSource};
+use evo_ir::Program;
+use evo_world::map::embed_map;
+use evo_world::IslandJob;
+use std::path::Path;
+
+/// Load an island job from a config file.
+///
+/// The file may hold either a bare `JobConfig` or a full `IslandJob` (for
+/// example one saved from `/api/jobs/request`), both as JSON. A bare config
+/// has no genomes; they must be supplied separately.
+pub fn load_job(path: &Path) -> Result<IslandJob> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config {}", path.display()))?;
+
+    let base = path.parent().unwrap_or(Path::new("."));
+    parse_job(&text, base).with_context(|| format!("Failed to parse config {}", path.display()))
+}
+
+/// Parse a job; relative map paths are resolved against `base`
+fn parse_job(text: &str, base: &Path) -> Result<IslandJob> {
+    let value: serde_json::Value = serde_json::from_str(text)?;
+
+    if value.get("job_id").is_some() && value.get("genomes").is_some() {
+        let mut job: IslandJob = serde_json::from_value(value)?;
+        resolve_map_path(&mut job.config, base);
+        embed_map(&mut job.config.world_config)?;
+        return Ok(job);
+    }
+
+    let mut config: JobConfig = serde_json::from_value(value)?;
+    resolve_map_path(&mut config, base);
+    Ok(IslandJob::new(JobId::new(), config, Vec::new())?)
+}
+
+fn resolve_map_path(config: &mut JobConfig, base: &Path) {
+    if let Some(MapSource::File(path)) = &mut config.world_config.map {
+        if Path::new(path.as_str()).is_relative() {
+            *path = base.join(&*path).to_string_lossy().into_owned();
+        }
+    }
+}
+
+/// Load a genome from disk.
+///
+/// `.json` files are read as a JSON `Program`; anything else is read as the
+/// bincode encoding produced by `Program::to_bytes`.
+pub fn load_genome(path: &Path) -> Result<Program> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("Failed to read genome {}", path.display()))?;
+
+    let is_json = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
+    let program = if is_json {
+        serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to parse genome {}", path.display()))?
+    } else {
+        Program::from_bytes(&bytes)
+            .with_context(|| format!("Failed to decode genome {}", path.display()))?
+    };
+
+    evo_ir::validate_program(&program)
+        .with_context(|| format!("Invalid genome {}", path.display()))?;
+
+    Ok(program)
+}
+
+/// Replace a job's genomes with the given files (one fresh lineage each)
+pub fn override_genomes(job: &mut IslandJob, paths: &[impl AsRef<Path>]) -> Result<()> {
+    let mut genomes = Vec::with_capacity(paths.len());
+    for path in paths {
+        let program = load_genome(path.as_ref())?;
+        genomes.push((LineageId::new(), program.to_bytes()?));
+    }
+
+    job.genomes = genomes;
+    Ok(())
+}
+
+/// Check that a job is runnable before starting the simulation
+pub fn check_job(job: &IslandJob) -> Result<()> {
+    if job.genomes.is_empty() {
+        bail!("No genomes to run; pass at least one --genome");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bare_config() {
+        let config = JobConfig {
+            num_ticks: 42,
+            ..Default::default()
+        };
+        let text = serde_json::to_string(&config).unwrap();
+
+        let job = parse_job(&text, Path::new(".")).unwrap();
+        assert_eq!(job.config.num_ticks, 42);
+        assert!(job.genomes.is_empty());
+        assert!(check_job(&job).is_err());
+    }
+
+    #[test]
+    fn test_parse_island_job() {
+        let job = IslandJob::new(
+            JobId::new(),
+            JobConfig::default(),
+            vec![(LineageId::new(), Program::new())],
+        )
+        .unwrap();
+        let text = serde_json::to_string(&job).unwrap();
+
+        let parsed = parse_job(&text, Path::new(".")).unwrap();
+        assert_eq!(parsed.job_id, job.job_id);
+        assert_eq!(parsed.genomes.len(), 1);
+    }
+}
@@ -0,0 +1,337 @@
+//! Headless command line interface for running island simulations locally.
+
+mod input;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use evo_core::JobId;
+use evo_world::{FrameRecorder, IslandResult, Replay, Simulation, SimulationSnapshot};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser)]
+#[command(name = "evo", version, about = "Run Evo-WASM island simulations locally")]
+struct Cli {
+    /// Log filter (overridden by RUST_LOG)
+    #[arg(long, global = true, default_value = "warn")]
+    log: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run an island job and write the result as JSON
+    Run(RunArgs),
+    /// Resume a run from a snapshot written by `evo run --snapshot`
+    Resume(ResumeArgs),
+    /// Reconstruct the world at a tick from a recorded event log
+    Replay(ReplayArgs),
+}
+
+#[derive(clap::Args)]
+struct RunArgs {
+    /// Job config file (JSON `JobConfig` or `IslandJob`)
+    #[arg(short, long)]
+    config: PathBuf,
+
+    /// Genome file to seed the island with (repeatable; replaces the job's genomes)
+    #[arg(short, long = "genome")]
+    genomes: Vec<PathBuf>,
+
+    /// Override the random seed
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Override the number of ticks
+    #[arg(long)]
+    ticks: Option<u64>,
+
+    /// Where to write the result (stdout if omitted)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Record a structured event log of the run to this file
+    #[arg(long)]
+    events: Option<PathBuf>,
+
+    #[command(flatten)]
+    frames: FrameArgs,
+
+    /// Stop before this tick and write a snapshot instead of a result
+    #[arg(long, requires = "snapshot")]
+    stop_at: Option<u64>,
+
+    /// Snapshot file written when stopping early
+    #[arg(long, requires = "stop_at")]
+    snapshot: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct ResumeArgs {
+    /// Snapshot file to resume from
+    snapshot: PathBuf,
+
+    /// Where to write the result (stdout if omitted)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Record a structured event log of the rest of the run to this file
+    #[arg(long)]
+    events: Option<PathBuf>,
+
+    #[command(flatten)]
+    frames: FrameArgs,
+}
+
+#[derive(clap::Args)]
+struct FrameArgs {
+    /// Write rendered PPM frames into this directory during the run
+    #[arg(long)]
+    frames: Option<PathBuf>,
+
+    /// Ticks between frames
+    #[arg(long, default_value_t = 100)]
+    frame_every: u64,
+
+    /// Pixels per tile in rendered images
+    #[arg(long, default_value_t = 4)]
+    scale: u32,
+}
+
+impl FrameArgs {
+    fn attach(&self, simulation: &mut Simulation) -> Result<()> {
+        if let Some(dir) = &self.frames {
+            let recorder = FrameRecorder::new(dir, self.frame_every, self.scale)
+                .with_context(|| format!("Failed to create frame directory {}", dir.display()))?;
+            simulation.record_frames(recorder);
+        }
+        Ok(())
+    }
+}
+
+#[derive(clap::Args)]
+struct ReplayArgs {
+    /// Event log written by `evo run --events`
+    events: PathBuf,
+
+    /// Tick to reconstruct (defaults to the last recorded tick)
+    #[arg(long)]
+    tick: Option<u64>,
+
+    /// Print the reconstructed organisms as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Render the reconstructed world to a PPM image
+    #[arg(long)]
+    image: Option<PathBuf>,
+
+    /// Pixels per tile in the rendered image
+    #[arg(long, default_value_t = 4)]
+    scale: u32,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&cli.log));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
+
+    match cli.command {
+        Command::Run(args) => run(args),
+        Command::Resume(args) => resume(args),
+        Command::Replay(args) => replay(args),
+    }
+}
+
+fn run(args: RunArgs) -> Result<()> {
+    let mut job = input::load_job(&args.config)?;
+
+    if !args.genomes.is_empty() {
+        input::override_genomes(&mut job, &args.genomes)?;
+    }
+    if let Some(seed) = args.seed {
+        job.config.seed = seed;
+    }
+    if let Some(ticks) = args.ticks {
+        job.config.num_ticks = ticks;
+    }
+    input::check_job(&job)?;
+
+    let mut simulation = job.simulation()?;
+
+    // Read the world size back from the simulation, since a map sets it
+    let world = &simulation.config().world_config;
+    eprintln!(
+        "Running job {} for {} ticks (seed {}, {} genomes, {}x{} world)",
+        job.job_id.0,
+        job.config.num_ticks,
+        job.config.seed,
+        job.genomes.len(),
+        world.width,
+        world.height,
+    );
+
+    if let Some(path) = &args.events {
+        record_events(&mut simulation, path)?;
+    }
+    args.frames.attach(&mut simulation)?;
+
+    if let (Some(stop_at), Some(path)) = (args.stop_at, &args.snapshot) {
+        simulation.run_until(stop_at).context("Simulation failed")?;
+
+        let bytes = simulation.snapshot().to_bytes()?;
+        std::fs::write(path, bytes)
+            .with_context(|| format!("Failed to write snapshot {}", path.display()))?;
+        eprintln!(
+            "Stopped after tick {}; snapshot written to {}",
+            simulation.tick(),
+            path.display()
+        );
+        return Ok(());
+    }
+
+    finish(simulation, job.job_id, args.output.as_deref())
+}
+
+fn resume(args: ResumeArgs) -> Result<()> {
+    let bytes = std::fs::read(&args.snapshot)
+        .with_context(|| format!("Failed to read snapshot {}", args.snapshot.display()))?;
+    let snapshot = SimulationSnapshot::from_bytes(&bytes)
+        .with_context(|| format!("Failed to decode snapshot {}", args.snapshot.display()))?;
+
+    eprintln!(
+        "Resuming after tick {} of {} ({} organisms)",
+        snapshot.tick,
+        snapshot.config.num_ticks,
+        snapshot.organisms.len()
+    );
+
+    let mut simulation = Simulation::restore(snapshot).context("Failed to restore snapshot")?;
+    if let Some(path) = &args.events {
+        record_events(&mut simulation, path)?;
+    }
+    args.frames.attach(&mut simulation)?;
+
+    // Snapshots don't carry the job id; results get a fresh one
+    finish(simulation, JobId::new(), args.output.as_deref())
+}
+
+fn record_events(simulation: &mut Simulation, path: &Path) -> Result<()> {
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create event log {}", path.display()))?;
+    simulation.record_events(BufWriter::new(file))?;
+    Ok(())
+}
+
+/// Run the simulation to the end and write its result
+fn finish(mut simulation: Simulation, job_id: JobId, output: Option<&Path>) -> Result<()> {
+    let start = Instant::now();
+    let result = IslandResult {
+        job_id,
+        result: simulation.run().context("Simulation failed")?,
+    };
+    let elapsed = start.elapsed();
+
+    print_summary(&result, elapsed.as_secs_f64());
+
+    let json = serde_json::to_string_pretty(&result)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, json)
+                .with_context(|| format!("Failed to write result {}", path.display()))?;
+            eprintln!("Result written to {}", path.display());
+        }
+        None => println!("{}", json),
+    }
+
+    Ok(())
+}
+
+fn replay(args: ReplayArgs) -> Result<()> {
+    let replay = Replay::open(&args.events)
+        .with_context(|| format!("Failed to read event log {}", args.events.display()))?;
+
+    let tick = match args.tick.or(replay.last_tick()) {
+        Some(tick) => tick,
+        None => bail!("Event log {} has no recorded ticks", args.events.display()),
+    };
+    let state = replay.state_at(tick);
+
+    if let Some(path) = &args.image {
+        state
+            .render(args.scale)
+            .save_ppm(path)
+            .with_context(|| format!("Failed to write image {}", path.display()))?;
+        eprintln!("Tick {} rendered to {}", tick, path.display());
+    }
+
+    if args.json {
+        let mut organisms: Vec<_> = state.organisms.values().collect();
+        organisms.sort_by_key(|o| (o.position.y, o.position.x));
+        println!("{}", serde_json::to_string_pretty(&organisms)?);
+        return Ok(());
+    }
+
+    let resources: i64 = state
+        .grid
+        .iter()
+        .map(|(_, tile)| tile.resource_amount as i64)
+        .sum();
+    println!(
+        "Tick {}: {} organisms, {} resources on the grid",
+        tick,
+        state.organisms.len(),
+        resources
+    );
+
+    let mut lineages: Vec<_> = state.lineage_counts().into_iter().collect();
+    lineages.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    for (lineage, count) in lineages {
+        println!("  lineage {}  organisms {}", lineage, count);
+    }
+
+    Ok(())
+}
+
+/// Print a short human-readable summary of a finished run to stderr
+fn print_summary(result: &IslandResult, elapsed_secs: f64) {
+    let sim = &result.result;
+
+    eprintln!(
+        "Finished {} ticks in {:.2}s: {} survivors, {} lineages, {} births",
+        sim.total_ticks,
+        elapsed_secs,
+        sim.survivors.len(),
+        sim.lineage_stats.len(),
+        sim.births.len(),
+    );
+
+    let mut best: Vec<(String, f64, usize)> = sim
+        .lineage_stats
+        .iter()
+        .map(|(id, metrics)| {
+            let fitness = metrics
+                .iter()
+                .map(|m| m.scalar_fitness())
+                .fold(0.0, f64::max);
+            (id.to_string(), fitness, metrics.len())
+        })
+        .collect();
+    best.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    for (lineage, fitness, count) in best.iter().take(5) {
+        eprintln!(
+            "  lineage {}  best fitness {:>10.1}  survivors {}",
+            lineage, fitness, count
+        );
+    }
+}
@@ -1,5 +1,6 @@
 //! Configuration types for the simulation.
 
+use crate::{BehaviorDescriptor, Error, Objective, ResourceKind, Result, Topology};
 use serde::{Deserialize, Serialize};
 
 /// World configuration parameters
@@ -21,6 +22,36 @@ pub struct WorldConfig {
     pub hazard_density: f32,
     /// Hazard damage per tick
     pub hazard_damage: i32,
+    /// How obstacles, hazards and resources are laid out
+    #[serde(default)]
+    pub terrain: TerrainConfig,
+    /// Hand-authored map; replaces generated terrain and sets the world size
+    #[serde(default)]
+    pub map: Option<MapSource>,
+    /// How plant stocks change each tick (at `resource_regen_rate`)
+    #[serde(default)]
+    pub plant_curve: RegenCurve,
+    /// Most plant units eaten in one bite
+    #[serde(default = "default_plant_bite_size")]
+    pub plant_bite_size: i32,
+    /// Value `env_read` reports for a tile holding plants
+    #[serde(default = "default_plant_sense_code")]
+    pub plant_sense_code: i32,
+    /// Meat yield, decay and sensing
+    #[serde(default = "ResourceKindConfig::meat")]
+    pub meat: ResourceKindConfig,
+    /// Meat left behind by dead organisms
+    #[serde(default)]
+    pub carcass: CarcassConfig,
+    /// Mutagenic zones
+    #[serde(default)]
+    pub radiation: RadiationConfig,
+    /// How the edges connect and which tiles are neighbors
+    #[serde(default)]
+    pub topology: Topology,
+    /// Sub-populations and migration between them
+    #[serde(default)]
+    pub regions: RegionConfig,
 }
 
 impl Default for WorldConfig {
@@ -34,10 +65,309 @@ impl Default for WorldConfig {
             obstacle_density: 0.05,
             hazard_density: 0.02,
             hazard_damage: 10,
+            terrain: TerrainConfig::default(),
+            map: None,
+            plant_curve: RegenCurve::Logistic,
+            plant_bite_size: default_plant_bite_size(),
+            plant_sense_code: default_plant_sense_code(),
+            meat: ResourceKindConfig::meat(),
+            carcass: CarcassConfig::default(),
+            radiation: RadiationConfig::default(),
+            topology: Topology::default(),
+            regions: RegionConfig::default(),
         }
     }
 }
 
+fn default_plant_bite_size() -> i32 {
+    100
+}
+
+fn default_plant_sense_code() -> i32 {
+    1
+}
+
+/// `env_read` codes of empty, obstacle and hazard tiles
+const RESERVED_SENSE_CODES: [i32; 3] = [0, 2, 3];
+
+impl WorldConfig {
+    /// Check that plants and meat read as something distinct from each
+    /// other and from the fixed tile codes
+    pub fn validate_sense_codes(&self) -> Result<()> {
+        let (plant, meat) = (self.plant_sense_code, self.meat.sense_code);
+        for (kind, code) in [("plant", plant), ("meat", meat)] {
+            if RESERVED_SENSE_CODES.contains(&code) {
+                return Err(Error::Validation(format!(
+                    "{} sense code {} is reserved for empty, obstacle or hazard tiles",
+                    kind, code
+                )));
+            }
+        }
+        if plant == meat {
+            return Err(Error::Validation(format!(
+                "plants and meat share the sense code {}",
+                plant
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check that meat only rots or keeps: it has no capacity of its own to
+    /// grow toward
+    pub fn validate_meat_curve(&self) -> Result<()> {
+        match self.meat.curve {
+            RegenCurve::Decay | RegenCurve::Static => Ok(()),
+            curve => Err(Error::Validation(format!(
+                "meat curve must be Decay or Static, not {:?}",
+                curve
+            ))),
+        }
+    }
+}
+
+/// How a tile's stock of a resource changes each tick.
+///
+/// Growth curves are capped by the tile's `max_resource`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum RegenCurve {
+    /// Logistic growth: fastest at half capacity
+    #[default]
+    Logistic,
+    /// Fixed fraction of capacity per tick
+    Linear,
+    /// Lose a fraction of the current stock per tick
+    Decay,
+    /// Never changes
+    Static,
+}
+
+impl RegenCurve {
+    /// Next stock level; every curve moves at least one unit per tick
+    pub fn apply(&self, amount: i32, max: i32, rate: f32) -> i32 {
+        match self {
+            RegenCurve::Logistic if amount < max => {
+                let growth = (rate * amount as f32 * (1.0 - amount as f32 / max as f32)) as i32;
+                (amount + growth.max(1)).min(max)
+            }
+            RegenCurve::Linear if amount < max => {
+                let growth = (rate * max as f32) as i32;
+                (amount + growth.max(1)).min(max)
+            }
+            RegenCurve::Decay => {
+                let loss = (rate * amount as f32) as i32;
+                (amount - loss.max(1)).max(0)
+            }
+            _ => amount,
+        }
+    }
+}
+
+/// Behaviour of one resource kind
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceKindConfig {
+    /// Energy gained per unit eaten
+    pub energy_yield: f32,
+    pub curve: RegenCurve,
+    /// Rate parameter of the curve
+    pub rate: f32,
+    /// Most units eaten in one bite
+    pub bite_size: i32,
+    /// Value `env_read` reports for a tile holding this resource
+    pub sense_code: i32,
+}
+
+impl ResourceKindConfig {
+    pub fn meat() -> Self {
+        Self {
+            energy_yield: 2.0,
+            curve: RegenCurve::Decay,
+            rate: 0.01,
+            bite_size: 200,
+            sense_code: 4,
+        }
+    }
+}
+
+/// Size of the carcass a dead organism leaves on its tile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CarcassConfig {
+    /// Fraction of the energy the organism held at the start of the tick it
+    /// died that turns into meat (converted at the meat energy yield)
+    pub energy_fraction: f32,
+    /// Meat units every carcass contains regardless of energy
+    pub body_mass: i32,
+}
+
+impl Default for CarcassConfig {
+    fn default() -> Self {
+        Self {
+            energy_fraction: 0.5,
+            body_mass: 100,
+        }
+    }
+}
+
+impl CarcassConfig {
+    /// Meat units left by an organism that held `energy` before dying
+    pub fn meat_for(&self, energy: i32, meat_yield: f32) -> i32 {
+        let from_energy = if meat_yield > 0.0 {
+            (energy.max(0) as f32 * self.energy_fraction / meat_yield) as i32
+        } else {
+            0
+        };
+        (from_energy + self.body_mass).max(0)
+    }
+}
+
+/// Radiation zones that raise the mutation rates of offspring born in them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RadiationConfig {
+    /// Fraction of tiles that are radioactive (0 disables radiation)
+    pub density: f32,
+    pub layout: TileLayout,
+    /// Mutagen level of a radioactive tile; rates are scaled by `1 + level`
+    pub level: f32,
+    /// Parents within this many tiles of radiation are affected
+    pub reach: i32,
+}
+
+impl Default for RadiationConfig {
+    fn default() -> Self {
+        Self {
+            density: 0.0,
+            layout: TileLayout::Clusters {
+                count: 3,
+                radius: 4.0,
+            },
+            level: 4.0,
+            reach: 1,
+        }
+    }
+}
+
+/// Sub-populations inside one world: vertical strips separated by barriers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegionConfig {
+    /// Number of regions (1 keeps the world well mixed)
+    pub count: u32,
+    /// Width of the obstacle barrier at the west edge of each region
+    pub barrier_width: i32,
+    /// Ticks between migrations (0 disables migration)
+    pub migration_interval: u64,
+    /// Fraction of each region's population that migrates each time
+    pub migration_rate: f32,
+    pub pattern: MigrationPattern,
+}
+
+impl Default for RegionConfig {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            barrier_width: 1,
+            migration_interval: 0,
+            migration_rate: 0.05,
+            pattern: MigrationPattern::Ring,
+        }
+    }
+}
+
+impl RegionConfig {
+    pub fn enabled(&self) -> bool {
+        self.count > 1
+    }
+
+    /// Whether migrants move at the end of `tick`
+    pub fn migrates_at(&self, tick: u64) -> bool {
+        self.enabled()
+            && self.migration_interval > 0
+            && (tick + 1).is_multiple_of(self.migration_interval)
+    }
+}
+
+/// Which regions migrants move to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MigrationPattern {
+    /// Region `i` sends migrants to `i + 1`, the last one to the first
+    #[default]
+    Ring,
+    /// Migrants move to a random adjacent region; the end regions only
+    /// have one neighbor
+    SteppingStone,
+}
+
+/// Where a hand-authored world map comes from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MapSource {
+    /// Path to an ASCII map file (read by whoever builds the job)
+    File(String),
+    /// ASCII map text carried inside the config
+    Inline(String),
+}
+
+/// Terrain generation: one layout per tile layer.
+///
+/// Layers are placed in order obstacles, hazards, resources, each on tiles
+/// not already taken. The densities in `WorldConfig` still set how much of
+/// the map each layer covers (except for walls and mazes, which have their
+/// own shape parameters). With every layer `Uniform` the grid is generated
+/// exactly as before terrain layouts existed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TerrainConfig {
+    pub obstacles: ObstacleLayout,
+    pub hazards: TileLayout,
+    pub resources: TileLayout,
+}
+
+impl TerrainConfig {
+    pub fn is_uniform(&self) -> bool {
+        self.obstacles == ObstacleLayout::Uniform
+            && self.hazards == TileLayout::Uniform
+            && self.resources == TileLayout::Uniform
+    }
+}
+
+/// Spatial layout of a tile layer
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum TileLayout {
+    /// Independent random tiles (salt-and-pepper)
+    #[default]
+    Uniform,
+    /// Highest points of a smooth value-noise field
+    Noise {
+        /// Feature size in tiles
+        scale: f32,
+        /// Number of noise octaves (more = rougher edges)
+        octaves: u32,
+    },
+    /// Circular patches around random centers (oases, hazard zones)
+    Clusters {
+        count: u32,
+        /// Patch radius in tiles
+        radius: f32,
+    },
+}
+
+/// Spatial layout of obstacles
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum ObstacleLayout {
+    #[default]
+    Uniform,
+    Noise { scale: f32, octaves: u32 },
+    Clusters { count: u32, radius: f32 },
+    /// Random horizontal and vertical wall segments
+    Walls {
+        count: u32,
+        min_length: i32,
+        max_length: i32,
+    },
+    /// A perfect maze covering the whole map
+    Maze {
+        /// Width of the corridors in tiles
+        corridor_width: i32,
+    },
+}
+
 /// Energy and cost configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnergyConfig {
@@ -57,6 +387,169 @@ pub struct EnergyConfig {
     pub eat_efficiency: f32,
     /// Minimum energy required to reproduce
     pub min_reproduce_energy: i32,
+    /// Costs of sexual reproduction
+    #[serde(default)]
+    pub mating: MatingConfig,
+    /// Fraction of energy lost when sharing it with a neighbor
+    #[serde(default = "default_share_loss")]
+    pub share_loss: f32,
+    /// How asexual offspring are paid for
+    #[serde(default)]
+    pub reproduction: ReproductionPolicy,
+    /// Senescence and lifespan
+    #[serde(default)]
+    pub aging: AgingConfig,
+}
+
+fn default_share_loss() -> f32 {
+    0.1
+}
+
+/// Energy rules for two organisms mating
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatingConfig {
+    /// Energy each parent pays (lost)
+    pub cost: i32,
+    /// Energy each parent needs to mate
+    pub min_energy: i32,
+    /// Fraction of each parent's energy left after the cost that goes to the
+    /// offspring; the offspring starts with the sum of both contributions
+    pub contribution: f32,
+}
+
+impl Default for MatingConfig {
+    fn default() -> Self {
+        Self {
+            cost: 150,
+            min_energy: 400,
+            contribution: 0.25,
+        }
+    }
+}
+
+/// Energy rules for asexual reproduction.
+///
+/// The parent pays `reproduce_cost` and hands its offspring a share of what
+/// is left, so births only create energy through subsidies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReproductionPolicy {
+    /// Energy the parent gives its offspring after paying the cost
+    pub offspring_energy: OffspringEnergy,
+    /// Energy created at birth, by tick range (ignored when conserving)
+    pub subsidies: Vec<Subsidy>,
+    /// Reproduction neither creates nor destroys energy: the cost goes to
+    /// the offspring and subsidies are ignored
+    pub conserve_energy: bool,
+}
+
+impl Default for ReproductionPolicy {
+    fn default() -> Self {
+        Self {
+            offspring_energy: OffspringEnergy::Fraction(0.5),
+            subsidies: Vec::new(),
+            conserve_energy: false,
+        }
+    }
+}
+
+impl ReproductionPolicy {
+    /// Energy taken from a parent with `available` energy left after the cost
+    pub fn offspring_share(&self, available: i32) -> i32 {
+        let available = available.max(0);
+        match self.offspring_energy {
+            OffspringEnergy::Fraction(fraction) => {
+                (available as f32 * fraction.clamp(0.0, 1.0)) as i32
+            }
+            OffspringEnergy::Fixed(amount) => amount.clamp(0, available),
+        }
+    }
+
+    /// Subsidy paid for births at `tick`, if any
+    pub fn subsidy_at(&self, tick: u64) -> Option<&Subsidy> {
+        if self.conserve_energy {
+            return None;
+        }
+        self.subsidies.iter().find(|s| s.applies_at(tick))
+    }
+}
+
+/// How organisms age. The defaults disable senescence.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgingConfig {
+    /// Age at which basal cost starts rising
+    pub onset: u64,
+    /// Extra basal cost per tick of age past `onset`
+    pub cost_per_tick: f32,
+    /// Organisms older than this die of old age
+    pub max_lifespan: Option<u64>,
+    /// Youngest age at which organisms can reproduce or mate
+    pub fertile_from: u64,
+    /// Oldest age at which organisms can reproduce or mate
+    pub fertile_until: Option<u64>,
+}
+
+impl Default for AgingConfig {
+    fn default() -> Self {
+        Self {
+            onset: 0,
+            cost_per_tick: 0.0,
+            max_lifespan: None,
+            fertile_from: 0,
+            fertile_until: None,
+        }
+    }
+}
+
+impl AgingConfig {
+    /// Basal cost for an organism of `age`
+    pub fn basal_cost(&self, base: i32, age: u64) -> i32 {
+        let past_onset = age.saturating_sub(self.onset) as f32;
+        base + (past_onset * self.cost_per_tick.max(0.0)) as i32
+    }
+
+    /// Whether an organism of `age` has outlived the maximum lifespan
+    pub fn outlived(&self, age: u64) -> bool {
+        self.max_lifespan.is_some_and(|max| age > max)
+    }
+
+    /// Whether an organism of `age` is in its reproductive window
+    pub fn is_fertile(&self, age: u64) -> bool {
+        age >= self.fertile_from && self.fertile_until.is_none_or(|until| age <= until)
+    }
+}
+
+/// How much of the parent's energy goes to an offspring
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OffspringEnergy {
+    /// Fraction of the parent's remaining energy
+    Fraction(f32),
+    /// Fixed amount, capped at the parent's remaining energy
+    Fixed(i32),
+}
+
+/// Energy created for births during a range of ticks
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Subsidy {
+    /// First tick the subsidy applies to
+    #[serde(default)]
+    pub from_tick: u64,
+    /// Tick the subsidy stops at (exclusive); open-ended if unset
+    #[serde(default)]
+    pub until_tick: Option<u64>,
+    /// Extra energy for the offspring
+    #[serde(default)]
+    pub offspring: i32,
+    /// Extra energy for the parent
+    #[serde(default)]
+    pub parent: i32,
+}
+
+impl Subsidy {
+    pub fn applies_at(&self, tick: u64) -> bool {
+        tick >= self.from_tick && self.until_tick.is_none_or(|until| tick < until)
+    }
 }
 
 impl Default for EnergyConfig {
@@ -70,6 +563,10 @@ impl Default for EnergyConfig {
             reproduce_cost: 300,  // Reduced from 500 to encourage more reproduction
             eat_efficiency: 1.5,  // Increased from 0.8 to create positive energy economy
             min_reproduce_energy: 400,  // Reduced from 600 to allow earlier reproduction
+            mating: MatingConfig::default(),
+            share_loss: default_share_loss(),
+            reproduction: ReproductionPolicy::default(),
+            aging: AgingConfig::default(),
         }
     }
 }
@@ -113,6 +610,147 @@ pub struct JobConfig {
     pub exec_config: ExecutionConfig,
     /// Dynamic rules (server-defined behavior)
     pub dynamic_rules: DynamicRules,
+    /// Seasons and scripted changes to the world over the run
+    #[serde(default)]
+    pub schedule: EnvironmentSchedule,
+}
+
+/// Changes to the world made by a season or scheduled event.
+///
+/// Unset fields are left as they are. Setting a layout or density re-lays
+/// that layer of the grid.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentChange {
+    pub resource_regen_rate: Option<f32>,
+    pub hazard_damage: Option<i32>,
+    pub resources: Option<TileLayout>,
+    pub resource_density: Option<f32>,
+    pub obstacles: Option<ObstacleLayout>,
+    pub obstacle_density: Option<f32>,
+}
+
+impl EnvironmentChange {
+    pub fn relayout_resources(&self) -> bool {
+        self.resources.is_some() || self.resource_density.is_some()
+    }
+
+    pub fn relayout_obstacles(&self) -> bool {
+        self.obstacles.is_some() || self.obstacle_density.is_some()
+    }
+
+    /// Update the world settings (the grid is re-laid separately)
+    pub fn apply_to(&self, world: &mut WorldConfig) {
+        if let Some(rate) = self.resource_regen_rate {
+            world.resource_regen_rate = rate;
+        }
+        if let Some(damage) = self.hazard_damage {
+            world.hazard_damage = damage;
+        }
+        if let Some(layout) = &self.resources {
+            world.terrain.resources = layout.clone();
+        }
+        if let Some(density) = self.resource_density {
+            world.resource_density = density;
+        }
+        if let Some(layout) = &self.obstacles {
+            world.terrain.obstacles = layout.clone();
+        }
+        if let Some(density) = self.obstacle_density {
+            world.obstacle_density = density;
+        }
+    }
+}
+
+/// One season of a repeating cycle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Season {
+    pub name: String,
+    /// Length in ticks
+    pub length: u64,
+    /// Applied when the season starts
+    pub changes: EnvironmentChange,
+}
+
+/// A one-off change at a given tick
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledEvent {
+    pub tick: u64,
+    pub changes: EnvironmentChange,
+}
+
+/// Time-varying environment: repeating seasons plus scripted events
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentSchedule {
+    /// Cycled in order from tick 0
+    pub seasons: Vec<Season>,
+    pub events: Vec<ScheduledEvent>,
+}
+
+impl EnvironmentSchedule {
+    pub fn is_empty(&self) -> bool {
+        self.seasons.is_empty() && self.events.is_empty()
+    }
+
+    /// Season in effect at `tick`, if any
+    pub fn season_at(&self, tick: u64) -> Option<&Season> {
+        self.season_start(tick).map(|(season, _)| season)
+    }
+
+    fn season_start(&self, tick: u64) -> Option<(&Season, u64)> {
+        let cycle: u64 = self.seasons.iter().map(|s| s.length).sum();
+        if cycle == 0 {
+            return None;
+        }
+
+        let mut offset = tick % cycle;
+        for season in self.seasons.iter().filter(|s| s.length > 0) {
+            if offset < season.length {
+                return Some((season, tick - offset));
+            }
+            offset -= season.length;
+        }
+        None
+    }
+
+    /// Changes taking effect at `tick`: a season starting, then events in
+    /// the order they are listed
+    pub fn changes_at(&self, tick: u64) -> Vec<&EnvironmentChange> {
+        let mut changes = Vec::new();
+        if let Some((season, start)) = self.season_start(tick) {
+            if start == tick {
+                changes.push(&season.changes);
+            }
+        }
+        changes.extend(
+            self.events
+                .iter()
+                .filter(|event| event.tick == tick)
+                .map(|event| &event.changes),
+        );
+        changes
+    }
+}
+
+impl JobConfig {
+    /// Effective settings for a resource kind.
+    ///
+    /// Plants are configured by the original world and energy fields
+    /// (`resource_regen_rate`, `eat_efficiency`) and the `plant_*` fields;
+    /// meat by `world_config.meat`.
+    pub fn resource_kind(&self, kind: ResourceKind) -> ResourceKindConfig {
+        match kind {
+            ResourceKind::Plant => ResourceKindConfig {
+                energy_yield: self.energy_config.eat_efficiency,
+                curve: self.world_config.plant_curve,
+                rate: self.world_config.resource_regen_rate,
+                bite_size: self.world_config.plant_bite_size,
+                sense_code: self.world_config.plant_sense_code,
+            },
+            ResourceKind::Meat => self.world_config.meat.clone(),
+        }
+    }
 }
 
 impl Default for JobConfig {
@@ -124,6 +762,7 @@ impl Default for JobConfig {
             energy_config: EnergyConfig::default(),
             exec_config: ExecutionConfig::default(),
             dynamic_rules: DynamicRules::default(),
+            schedule: EnvironmentSchedule::default(),
         }
     }
 }
@@ -135,8 +774,14 @@ pub struct DynamicRules {
     pub allow_combat: bool,
     /// Allow organisms to reproduce
     pub allow_reproduction: bool,
+    /// Allow two neighbors to reproduce sexually (needs `allow_reproduction`)
+    #[serde(default = "default_allow_mating")]
+    pub allow_mating: bool,
     /// Mutation rate for offspring (0.0 to 1.0)
     pub mutation_rate: f32,
+    /// Selection bonus per unit of energy given to neighbors
+    #[serde(default)]
+    pub cooperation_weight: f64,
     /// Maximum number of organisms in the simulation
     pub max_population: usize,
     /// Custom parameters for experimental features
@@ -148,13 +793,19 @@ impl Default for DynamicRules {
         Self {
             allow_combat: true,
             allow_reproduction: true,
+            allow_mating: true,
             mutation_rate: 0.01,
+            cooperation_weight: 0.0,
             max_population: 1000,
             custom_params: std::collections::HashMap::new(),
         }
     }
 }
 
+fn default_allow_mating() -> bool {
+    true
+}
+
 /// Server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
@@ -170,6 +821,21 @@ pub struct ServerConfig {
     pub checkpoint_interval_secs: u64,
     /// OpenTelemetry endpoint
     pub otel_endpoint: Option<String>,
+    /// Island model: demes of lineages and migration between them
+    #[serde(default)]
+    pub demes: DemeConfig,
+    /// Speciation and fitness sharing in selection
+    #[serde(default)]
+    pub species: SpeciesConfig,
+    /// MAP-Elites archive of the best genome per behavior niche
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+    /// Novelty search over behavior characterizations
+    #[serde(default)]
+    pub novelty: NoveltyConfig,
+    /// Best genomes ever found, and benchmarks against them
+    #[serde(default)]
+    pub hall_of_fame: HallOfFameConfig,
 }
 
 impl Default for ServerConfig {
@@ -181,10 +847,228 @@ impl Default for ServerConfig {
             checkpoint_dir: "./data/checkpoints".to_string(),
             checkpoint_interval_secs: 300, // 5 minutes
             otel_endpoint: None,
+            demes: DemeConfig::default(),
+            species: SpeciesConfig::default(),
+            archive: ArchiveConfig::default(),
+            novelty: NoveltyConfig::default(),
+            hall_of_fame: HallOfFameConfig::default(),
+        }
+    }
+}
+
+/// How the server splits the genome bank into demes.
+///
+/// Jobs are handed out to the demes in turn and draw their genomes from
+/// their own deme, plus a few migrants picked by the policy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DemeConfig {
+    /// Number of demes (1 keeps a single shared bank)
+    pub count: u32,
+    /// Fraction of a job's genomes that are migrants from other demes
+    pub migration_rate: f32,
+    pub policy: MigrationPolicy,
+}
+
+impl Default for DemeConfig {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            migration_rate: 0.1,
+            policy: MigrationPolicy::Ring,
+        }
+    }
+}
+
+impl DemeConfig {
+    pub fn enabled(&self) -> bool {
+        self.count > 1
+    }
+
+    /// Migrants in a job of `genomes` genomes
+    pub fn migrants_per_job(&self, genomes: usize) -> usize {
+        if !self.enabled() {
+            return 0;
+        }
+        ((genomes as f32 * self.migration_rate.clamp(0.0, 1.0)).round() as usize).min(genomes)
+    }
+}
+
+/// NEAT-style speciation of the genome bank.
+///
+/// Lineages whose genomes are closer than `threshold` to a species'
+/// representative belong to it. Selection divides a lineage's fitness by
+/// the size of its species, and new species keep their best lineage for a
+/// few selection rounds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeciesConfig {
+    /// Genome distance (0 to 1) within which lineages are one species;
+    /// 0 (the default) disables speciation
+    pub threshold: f64,
+    /// Selection rounds a new species is protected for
+    pub protected_generations: u32,
+}
+
+impl Default for SpeciesConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.0,
+            protected_generations: 3,
+        }
+    }
+}
+
+impl SpeciesConfig {
+    pub fn enabled(&self) -> bool {
+        self.threshold > 0.0
+    }
+}
+
+/// MAP-Elites archive: a grid over behavior descriptors where each cell
+/// keeps the fittest genome seen with that behavior
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    pub enabled: bool,
+    /// One grid dimension per axis
+    pub axes: Vec<ArchiveAxis>,
+    /// Fraction of each job's genomes sampled from the archive (the rest
+    /// are picked by rank as usual)
+    pub job_fraction: f32,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            axes: vec![
+                ArchiveAxis {
+                    descriptor: BehaviorDescriptor::Exploration,
+                    max: 200.0,
+                    bins: 10,
+                },
+                ArchiveAxis {
+                    descriptor: BehaviorDescriptor::Aggression,
+                    max: 500.0,
+                    bins: 10,
+                },
+            ],
+            job_fraction: 0.5,
+        }
+    }
+}
+
+/// One dimension of the archive: `bins` equal bins from 0 to `max`, with
+/// larger values going in the last bin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveAxis {
+    pub descriptor: BehaviorDescriptor,
+    pub max: f64,
+    pub bins: u32,
+}
+
+impl ArchiveAxis {
+    /// Bin a behavior value falls into
+    pub fn bin(&self, value: f64) -> u32 {
+        let bins = self.bins.max(1);
+        let fraction = if self.max > 0.0 { value / self.max } else { 0.0 };
+        ((fraction.max(0.0) * bins as f64) as u32).min(bins - 1)
+    }
+}
+
+/// Novelty search: rank lineages by how different their behavior is from
+/// what has been seen before, instead of by fitness
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoveltyConfig {
+    pub enabled: bool,
+    /// Nearest neighbors averaged into a behavior's sparseness
+    pub k: usize,
+    /// Sparseness above which a behavior joins the novelty archive
+    pub archive_threshold: f64,
+    /// Behaviors kept in the archive; the oldest are dropped first
+    pub max_archive: usize,
+}
+
+impl Default for NoveltyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            k: 15,
+            archive_threshold: 0.5,
+            max_archive: 2000,
+        }
+    }
+}
+
+/// Persisted hall of fame of the best genomes per objective
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HallOfFameConfig {
+    /// Genomes kept per objective (0 disables the hall of fame)
+    pub size: usize,
+    pub objectives: Vec<Objective>,
+    pub benchmark: BenchmarkConfig,
+}
+
+impl Default for HallOfFameConfig {
+    fn default() -> Self {
+        Self {
+            size: 10,
+            objectives: vec![
+                Objective::Fitness,
+                Objective::Lifetime,
+                Objective::Offspring,
+                Objective::Exploration,
+            ],
+            benchmark: BenchmarkConfig::default(),
+        }
+    }
+}
+
+/// Benchmark rounds: the current champions against the hall of fame's best
+/// (by its first objective), on islands with fixed seeds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BenchmarkConfig {
+    /// Jobs created between rounds (0 disables benchmarking)
+    pub interval: u64,
+    /// Seeds of the islands every round is played on
+    pub seeds: Vec<u64>,
+    /// Genomes on each side
+    pub competitors: usize,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            interval: 0,
+            seeds: vec![1, 2, 3],
+            competitors: 5,
         }
     }
 }
 
+impl BenchmarkConfig {
+    /// Whether a round is due after the `jobs`-th job was created
+    pub fn due_after(&self, jobs: u64) -> bool {
+        self.interval > 0 && jobs > 0 && jobs.is_multiple_of(self.interval)
+    }
+}
+
+/// Where a deme's migrants come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MigrationPolicy {
+    /// The best lineages of the previous deme in a ring
+    #[default]
+    Ring,
+    /// Random lineages from any other deme
+    Random,
+    /// The best lineages of all other demes
+    EliteBroadcast,
+}
+
 /// Worker configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkerConfig {
@@ -232,6 +1116,35 @@ mod tests {
         assert_eq!(job_config.num_ticks, 10_000);
     }
 
+    #[test]
+    fn test_sense_codes() {
+        let mut config = WorldConfig::default();
+        assert!(config.validate_sense_codes().is_ok());
+
+        for code in [0, 1, 2, 3] {
+            config.meat.sense_code = code;
+            assert!(config.validate_sense_codes().is_err(), "meat code {}", code);
+        }
+
+        // Plants can move out of the way
+        config.plant_sense_code = 7;
+        config.meat.sense_code = 1;
+        assert!(config.validate_sense_codes().is_ok());
+    }
+
+    #[test]
+    fn test_meat_cannot_grow() {
+        let mut config = WorldConfig::default();
+        assert!(config.validate_meat_curve().is_ok());
+        config.meat.curve = RegenCurve::Static;
+        assert!(config.validate_meat_curve().is_ok());
+
+        for curve in [RegenCurve::Logistic, RegenCurve::Linear] {
+            config.meat.curve = curve;
+            assert!(config.validate_meat_curve().is_err(), "{:?}", curve);
+        }
+    }
+
     #[test]
     fn test_dynamic_rules_serialization() {
         let rules = DynamicRules::default();
@@ -240,4 +1153,84 @@ mod tests {
         assert_eq!(rules.allow_combat, deserialized.allow_combat);
         assert_eq!(rules.mutation_rate, deserialized.mutation_rate);
     }
+
+    #[test]
+    fn test_schedule_changes_at() {
+        let season = |name: &str, length, rate| Season {
+            name: name.to_string(),
+            length,
+            changes: EnvironmentChange {
+                resource_regen_rate: Some(rate),
+                ..Default::default()
+            },
+        };
+        let schedule = EnvironmentSchedule {
+            seasons: vec![season("summer", 10, 0.2), season("winter", 5, 0.01)],
+            events: vec![ScheduledEvent {
+                tick: 27,
+                changes: EnvironmentChange {
+                    hazard_damage: Some(50),
+                    ..Default::default()
+                },
+            }],
+        };
+
+        assert_eq!(schedule.season_at(12).unwrap().name, "winter");
+        assert_eq!(schedule.season_at(31).unwrap().name, "summer");
+        assert_eq!(schedule.changes_at(0).len(), 1);
+        assert!(schedule.changes_at(3).is_empty());
+        assert_eq!(schedule.changes_at(10)[0].resource_regen_rate, Some(0.01));
+        // Summer starts again at tick 15 and 30; the event fires mid-season
+        assert_eq!(schedule.changes_at(30)[0].resource_regen_rate, Some(0.2));
+        assert_eq!(schedule.changes_at(27)[0].hazard_damage, Some(50));
+
+        let mut world = WorldConfig::default();
+        schedule.changes_at(10)[0].apply_to(&mut world);
+        assert_eq!(world.resource_regen_rate, 0.01);
+    }
+
+    #[test]
+    fn test_reproduction_policy() {
+        let mut policy = ReproductionPolicy {
+            offspring_energy: OffspringEnergy::Fixed(300),
+            subsidies: vec![Subsidy {
+                from_tick: 0,
+                until_tick: Some(100),
+                offspring: 200,
+                parent: 0,
+            }],
+            conserve_energy: false,
+        };
+        assert_eq!(policy.offspring_share(1000), 300);
+        assert_eq!(policy.offspring_share(120), 120);
+        assert_eq!(policy.subsidy_at(99).unwrap().offspring, 200);
+        assert!(policy.subsidy_at(100).is_none());
+
+        policy.conserve_energy = true;
+        assert!(policy.subsidy_at(0).is_none());
+        assert_eq!(ReproductionPolicy::default().offspring_share(801), 400);
+    }
+
+    #[test]
+    fn test_aging() {
+        let aging = AgingConfig {
+            onset: 100,
+            cost_per_tick: 0.05,
+            max_lifespan: Some(500),
+            fertile_from: 20,
+            fertile_until: Some(300),
+        };
+        assert_eq!(aging.basal_cost(1, 50), 1);
+        assert_eq!(aging.basal_cost(1, 300), 11);
+        assert!(!aging.outlived(500));
+        assert!(aging.outlived(501));
+        assert!(!aging.is_fertile(19));
+        assert!(aging.is_fertile(300));
+        assert!(!aging.is_fertile(301));
+
+        let default = AgingConfig::default();
+        assert_eq!(default.basal_cost(1, 10_000), 1);
+        assert!(!default.outlived(u64::MAX));
+        assert!(default.is_fertile(0));
+    }
 }
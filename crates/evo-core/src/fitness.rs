@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::LineageId;
+use crate::{LineageId, ResourceKind};
 
 /// Fitness metrics for an organism
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -23,6 +23,24 @@ pub struct FitnessMetrics {
     pub damage_dealt: i64,
     /// Total damage received
     pub damage_received: i64,
+    /// Plant units eaten
+    #[serde(default)]
+    pub plant_eaten: i64,
+    /// Meat units eaten
+    #[serde(default)]
+    pub meat_eaten: i64,
+    /// Energy given to neighbors (before transfer loss)
+    #[serde(default)]
+    pub energy_given: i64,
+    /// Energy received from neighbors
+    #[serde(default)]
+    pub energy_received: i64,
+    /// Part of `energy_given` that went to the same lineage
+    #[serde(default)]
+    pub kin_energy_given: i64,
+    /// Part of `energy_received` that came from the same lineage
+    #[serde(default)]
+    pub kin_energy_received: i64,
     /// Custom metrics
     pub custom: HashMap<String, f64>,
 }
@@ -32,6 +50,25 @@ impl FitnessMetrics {
         Self::default()
     }
 
+    /// Record a meal for diet tracking
+    pub fn record_meal(&mut self, kind: ResourceKind, amount: i32) {
+        self.times_eaten += 1;
+        match kind {
+            ResourceKind::Plant => self.plant_eaten += amount as i64,
+            ResourceKind::Meat => self.meat_eaten += amount as i64,
+        }
+    }
+
+    /// Fraction of everything eaten that was meat (0.0 = herbivore, 1.0 = carnivore)
+    pub fn meat_fraction(&self) -> f64 {
+        let total = self.plant_eaten + self.meat_eaten;
+        if total > 0 {
+            self.meat_eaten as f64 / total as f64
+        } else {
+            0.0
+        }
+    }
+
     /// Compute a simple scalar fitness (for basic ranking)
     pub fn scalar_fitness(&self) -> f64 {
         // Weighted combination of metrics
@@ -44,6 +81,12 @@ impl FitnessMetrics {
         lifetime_score + energy_score + offspring_score + exploration_score + combat_score
     }
 
+    /// Scalar fitness plus a bonus for energy given away, so selection can
+    /// favour cooperation (`DynamicRules::cooperation_weight`)
+    pub fn weighted_fitness(&self, cooperation_weight: f64) -> f64 {
+        self.scalar_fitness() + self.energy_given as f64 * cooperation_weight
+    }
+
     /// Check if this organism dominates another (for Pareto ranking)
     pub fn dominates(&self, other: &FitnessMetrics) -> bool {
         let mut better_in_any = false;
@@ -78,6 +121,62 @@ impl FitnessMetrics {
     }
 }
 
+/// A behavior measured from an organism's metrics, used to place it in a
+/// quality-diversity archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BehaviorDescriptor {
+    /// Distinct tiles visited
+    Exploration,
+    /// Damage dealt to others
+    Aggression,
+    /// Fraction of food eaten that was meat (0 to 1)
+    Diet,
+    /// Ticks survived
+    Lifetime,
+    /// Offspring produced
+    Fecundity,
+    /// Energy given to neighbors
+    Cooperation,
+}
+
+impl BehaviorDescriptor {
+    pub fn value(&self, metrics: &FitnessMetrics) -> f64 {
+        match self {
+            BehaviorDescriptor::Exploration => metrics.tiles_explored as f64,
+            BehaviorDescriptor::Aggression => metrics.damage_dealt as f64,
+            BehaviorDescriptor::Diet => metrics.meat_fraction(),
+            BehaviorDescriptor::Lifetime => metrics.lifetime as f64,
+            BehaviorDescriptor::Fecundity => metrics.offspring_count as f64,
+            BehaviorDescriptor::Cooperation => metrics.energy_given as f64,
+        }
+    }
+}
+
+/// Something a hall of fame ranks genomes by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Objective {
+    /// `FitnessMetrics::weighted_fitness`
+    Fitness,
+    Lifetime,
+    Offspring,
+    NetEnergy,
+    Exploration,
+    Kills,
+}
+
+impl Objective {
+    pub fn score(&self, metrics: &FitnessMetrics, cooperation_weight: f64) -> f64 {
+        match self {
+            Objective::Fitness => metrics.weighted_fitness(cooperation_weight),
+            Objective::Lifetime => metrics.lifetime as f64,
+            Objective::Offspring => metrics.offspring_count as f64,
+            Objective::NetEnergy => metrics.net_energy as f64,
+            Objective::Exploration => metrics.tiles_explored as f64,
+            Objective::Kills => metrics.kills as f64,
+        }
+    }
+}
+
 /// Lineage statistics aggregated across all organisms in a lineage
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LineageStats {
@@ -144,6 +243,10 @@ mod tests {
 
         let fitness = metrics.scalar_fitness();
         assert!(fitness > 0.0);
+
+        metrics.energy_given = 200;
+        assert_eq!(metrics.weighted_fitness(0.0), fitness);
+        assert_eq!(metrics.weighted_fitness(0.5), fitness + 100.0);
     }
 
     #[test]
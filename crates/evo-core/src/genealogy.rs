@@ -0,0 +1,306 @@
+//! Genealogy tracking and phylogeny export.
+
+use crate::LineageId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Parent references and generation number for a genome lineage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineageRecord {
+    pub lineage_id: LineageId,
+    /// Parent lineages (empty for founders, two for crossover offspring)
+    pub parents: Vec<LineageId>,
+    /// Number of breeding steps since the founding genome
+    pub generation: u32,
+}
+
+impl LineageRecord {
+    /// Record for a genome with no known parents
+    pub fn founder(lineage_id: LineageId) -> Self {
+        Self {
+            lineage_id,
+            parents: Vec::new(),
+            generation: 0,
+        }
+    }
+
+    /// Record for a genome bred from the given parents
+    pub fn offspring(lineage_id: LineageId, parents: &[&LineageRecord]) -> Self {
+        let mut parent_ids: Vec<LineageId> = Vec::with_capacity(parents.len());
+        for parent in parents {
+            if !parent_ids.contains(&parent.lineage_id) {
+                parent_ids.push(parent.lineage_id);
+            }
+        }
+
+        let generation = parents
+            .iter()
+            .map(|p| p.generation + 1)
+            .max()
+            .unwrap_or(0);
+
+        Self {
+            lineage_id,
+            parents: parent_ids,
+            generation,
+        }
+    }
+
+    /// The parent used as the tree edge in phylogeny exports
+    pub fn primary_parent(&self) -> Option<LineageId> {
+        self.parents.first().copied()
+    }
+}
+
+/// A single node of an exported phylogeny
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhylogenyNode {
+    pub lineage_id: LineageId,
+    /// Primary parent, if it is part of the phylogeny
+    pub parent_id: Option<LineageId>,
+    /// All recorded parents (including ones outside the phylogeny)
+    pub parents: Vec<LineageId>,
+    pub generation: u32,
+    pub children: Vec<LineageId>,
+}
+
+/// Flat phylogeny export suitable for tree visualization tools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhylogenyExport {
+    pub roots: Vec<LineageId>,
+    pub nodes: Vec<PhylogenyNode>,
+}
+
+/// Phylogenetic tree built from genealogy records.
+///
+/// Crossover offspring have two parents, so the genealogy is a DAG. The tree
+/// follows each lineage's primary (first) parent; the full parent list is kept
+/// in the JSON export.
+pub struct Phylogeny {
+    records: HashMap<LineageId, LineageRecord>,
+    children: HashMap<LineageId, Vec<LineageId>>,
+    roots: Vec<LineageId>,
+}
+
+impl Phylogeny {
+    pub fn from_records(records: impl IntoIterator<Item = LineageRecord>) -> Self {
+        let records: HashMap<LineageId, LineageRecord> = records
+            .into_iter()
+            .map(|r| (r.lineage_id, r))
+            .collect();
+
+        let mut children: HashMap<LineageId, Vec<LineageId>> = HashMap::new();
+        let mut roots = Vec::new();
+
+        for record in records.values() {
+            match record.primary_parent() {
+                Some(parent) if records.contains_key(&parent) => {
+                    children.entry(parent).or_default().push(record.lineage_id);
+                }
+                _ => roots.push(record.lineage_id),
+            }
+        }
+
+        let sort_key = |id: &LineageId| (records[id].generation, id.0);
+        roots.sort_by_key(sort_key);
+        for kids in children.values_mut() {
+            kids.sort_by_key(sort_key);
+        }
+
+        Self {
+            records,
+            children,
+            roots,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn roots(&self) -> &[LineageId] {
+        &self.roots
+    }
+
+    pub fn get(&self, lineage_id: LineageId) -> Option<&LineageRecord> {
+        self.records.get(&lineage_id)
+    }
+
+    pub fn children(&self, lineage_id: LineageId) -> &[LineageId] {
+        self.children
+            .get(&lineage_id)
+            .map(|c| c.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Export the tree in Newick format.
+    ///
+    /// Branch lengths are generation differences between parent and child.
+    /// Multiple roots are joined under an unnamed root node.
+    pub fn to_newick(&self) -> String {
+        let mut out = String::new();
+
+        if self.roots.len() != 1 {
+            out.push('(');
+        }
+        for (i, root) in self.roots.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            self.write_newick_subtree(*root, &mut out);
+        }
+        if self.roots.len() != 1 {
+            out.push(')');
+        }
+
+        out.push(';');
+        out
+    }
+
+    /// Write one subtree without recursion (genealogies can be very deep)
+    fn write_newick_subtree(&self, root: LineageId, out: &mut String) {
+        enum Visit {
+            Enter(LineageId),
+            Exit(LineageId),
+            Separator,
+        }
+
+        let mut stack = vec![Visit::Enter(root)];
+        let mut visited = HashSet::new();
+
+        while let Some(visit) = stack.pop() {
+            match visit {
+                Visit::Enter(id) => {
+                    let kids = self.children(id);
+                    if kids.is_empty() || !visited.insert(id) {
+                        self.write_newick_label(id, out);
+                        continue;
+                    }
+
+                    out.push('(');
+                    stack.push(Visit::Exit(id));
+                    for (i, kid) in kids.iter().enumerate().rev() {
+                        stack.push(Visit::Enter(*kid));
+                        if i > 0 {
+                            stack.push(Visit::Separator);
+                        }
+                    }
+                }
+                Visit::Exit(id) => {
+                    out.push(')');
+                    self.write_newick_label(id, out);
+                }
+                Visit::Separator => out.push(','),
+            }
+        }
+    }
+
+    fn write_newick_label(&self, id: LineageId, out: &mut String) {
+        let record = &self.records[&id];
+        let _ = write!(out, "{}", id);
+
+        if let Some(parent) = record
+            .primary_parent()
+            .and_then(|p| self.records.get(&p))
+        {
+            let length = record.generation.saturating_sub(parent.generation);
+            let _ = write!(out, ":{}", length);
+        }
+    }
+
+    /// Export the tree as a flat node list (one entry per lineage)
+    pub fn to_export(&self) -> PhylogenyExport {
+        let mut nodes: Vec<PhylogenyNode> = self
+            .records
+            .values()
+            .map(|record| PhylogenyNode {
+                lineage_id: record.lineage_id,
+                parent_id: record
+                    .primary_parent()
+                    .filter(|p| self.records.contains_key(p)),
+                parents: record.parents.clone(),
+                generation: record.generation,
+                children: self.children(record.lineage_id).to_vec(),
+            })
+            .collect();
+
+        nodes.sort_by_key(|n| (n.generation, n.lineage_id.0));
+
+        PhylogenyExport {
+            roots: self.roots.clone(),
+            nodes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offspring_generation() {
+        let a = LineageRecord::founder(LineageId::new());
+        let b = LineageRecord::offspring(LineageId::new(), &[&a]);
+        let c = LineageRecord::offspring(LineageId::new(), &[&a, &b]);
+
+        assert_eq!(b.generation, 1);
+        assert_eq!(c.generation, 2);
+        assert_eq!(c.parents, vec![a.lineage_id, b.lineage_id]);
+
+        // Selfing only records the parent once
+        let d = LineageRecord::offspring(LineageId::new(), &[&b, &b]);
+        assert_eq!(d.parents, vec![b.lineage_id]);
+    }
+
+    #[test]
+    fn test_newick_export() {
+        let root = LineageRecord::founder(LineageId::new());
+        let child = LineageRecord::offspring(LineageId::new(), &[&root]);
+        let grandchild = LineageRecord::offspring(LineageId::new(), &[&child]);
+
+        let phylogeny = Phylogeny::from_records(vec![
+            root.clone(),
+            child.clone(),
+            grandchild.clone(),
+        ]);
+        let newick = phylogeny.to_newick();
+
+        assert_eq!(
+            newick,
+            format!(
+                "(({}:1){}:1){};",
+                grandchild.lineage_id, child.lineage_id, root.lineage_id
+            )
+        );
+    }
+
+    #[test]
+    fn test_newick_multiple_roots() {
+        let a = LineageRecord::founder(LineageId::new());
+        let b = LineageRecord::founder(LineageId::new());
+        let phylogeny = Phylogeny::from_records(vec![a, b]);
+
+        let newick = phylogeny.to_newick();
+        assert!(newick.starts_with('('));
+        assert!(newick.ends_with(");"));
+        assert_eq!(phylogeny.roots().len(), 2);
+    }
+
+    #[test]
+    fn test_export_with_missing_parent() {
+        let missing = LineageRecord::founder(LineageId::new());
+        let orphan = LineageRecord::offspring(LineageId::new(), &[&missing]);
+        let phylogeny = Phylogeny::from_records(vec![orphan.clone()]);
+
+        let export = phylogeny.to_export();
+        assert_eq!(export.roots, vec![orphan.lineage_id]);
+        assert_eq!(export.nodes.len(), 1);
+        assert_eq!(export.nodes[0].parent_id, None);
+        assert_eq!(export.nodes[0].parents, vec![missing.lineage_id]);
+    }
+}
@@ -4,8 +4,10 @@ pub mod types;
 pub mod config;
 pub mod error;
 pub mod fitness;
+pub mod genealogy;
 
 pub use error::{Error, Result};
 pub use types::*;
 pub use config::*;
 pub use fitness::*;
+pub use genealogy::*;
@@ -1,5 +1,6 @@
 //! Core type definitions for the simulation.
 
+use crate::config::ResourceKindConfig;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use uuid::Uuid;
@@ -50,6 +51,11 @@ impl OrganismId {
     pub fn new() -> Self {
         Self(Uuid::new_v4())
     }
+
+    /// Build a v4 id from caller-supplied random bytes (e.g. a seeded RNG)
+    pub fn from_random_bytes(bytes: [u8; 16]) -> Self {
+        Self(uuid::Builder::from_random_bytes(bytes).into_uuid())
+    }
 }
 
 impl Default for OrganismId {
@@ -130,6 +136,82 @@ impl Direction {
             Direction::SouthWest,
         ]
     }
+
+    /// Neighbor slot used by the organism ABI (wraps around the 8 directions)
+    pub fn from_slot(slot: i32) -> Direction {
+        Self::all()[slot.rem_euclid(8) as usize]
+    }
+}
+
+/// How the edges of the world connect and which tiles are neighbors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Topology {
+    /// Both axes wrap around
+    #[default]
+    Torus,
+    /// Hard walls on every edge
+    Box,
+    /// Wraps left to right, with walls at the top and bottom
+    Cylinder,
+    /// Hexagonal tiles wrapping on both axes, in "odd-r" layout (odd rows
+    /// sit half a tile to the right). Each tile has six neighbors: E, W,
+    /// NE, NW, SE and SW. Use an even height so rows wrap cleanly.
+    Hex,
+}
+
+impl Topology {
+    /// The tile `pos` refers to, or None if it is past a wall
+    pub fn resolve(self, pos: Position, width: i32, height: i32) -> Option<Position> {
+        let inside_x = (0..width).contains(&pos.x);
+        let inside_y = (0..height).contains(&pos.y);
+        match self {
+            Topology::Torus | Topology::Hex => Some(pos.wrap(width, height)),
+            Topology::Box => (inside_x && inside_y).then_some(pos),
+            Topology::Cylinder => inside_y.then(|| pos.wrap(width, height)),
+        }
+    }
+
+    /// Target of a move by (`dx`, `dy`) from `pos`, before resolving.
+    ///
+    /// Square grids add the offset; on hex grids a move is a single step
+    /// toward it, and purely vertical moves zig-zag between NE/NW and SE/SW.
+    pub fn offset(self, pos: Position, dx: i32, dy: i32) -> Position {
+        if self != Topology::Hex || dy == 0 {
+            return pos.add(if self == Topology::Hex { dx.signum() } else { dx }, dy);
+        }
+        // The rows above and below hold columns x-1 and x (even rows) or
+        // x and x+1 (odd rows)
+        let odd_row = pos.y.rem_euclid(2) == 1;
+        let dx = match (dx.signum(), odd_row) {
+            (-1, false) => -1,
+            (1, true) => 1,
+            _ => 0,
+        };
+        pos.add(dx, dy.signum())
+    }
+
+    /// Neighbor of `pos` in a direction, before resolving. Hex grids have
+    /// no N or S neighbors.
+    pub fn step(self, pos: Position, direction: Direction) -> Option<Position> {
+        let (dx, dy) = direction.to_delta();
+        if self == Topology::Hex && dx == 0 {
+            return None;
+        }
+        Some(self.offset(pos, dx, dy))
+    }
+
+    /// Steps between two unresolved positions: Chebyshev distance on
+    /// square grids, hex distance on hex grids
+    pub fn distance(self, from: Position, to: Position) -> i32 {
+        if self != Topology::Hex {
+            return (to.x - from.x).abs().max((to.y - from.y).abs());
+        }
+        // Convert odd-r offset coordinates to axial ones
+        let axial = |p: Position| (p.x - (p.y - p.y.rem_euclid(2)) / 2, p.y);
+        let ((q1, r1), (q2, r2)) = (axial(from), axial(to));
+        let (dq, dr) = (q2 - q1, r2 - r1);
+        (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+    }
 }
 
 /// Tile type in the world
@@ -141,12 +223,41 @@ pub enum TileType {
     Hazard,
 }
 
+/// Kinds of food a tile can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResourceKind {
+    /// Regrowing vegetation on `TileType::Resource` tiles
+    Plant,
+    /// Carrion lying on any passable tile
+    Meat,
+}
+
+impl ResourceKind {
+    pub const ALL: [ResourceKind; 2] = [ResourceKind::Plant, ResourceKind::Meat];
+
+    /// Kind selected by an organism's `eat_kind` argument
+    pub fn from_code(code: i32) -> Option<Self> {
+        match code {
+            0 => Some(ResourceKind::Plant),
+            1 => Some(ResourceKind::Meat),
+            _ => None,
+        }
+    }
+}
+
 /// Tile state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tile {
     pub tile_type: TileType,
+    /// Plant stock (only meaningful on resource tiles)
     pub resource_amount: i32,
     pub max_resource: i32,
+    /// Meat stock
+    #[serde(default)]
+    pub meat_amount: i32,
+    /// Mutagen level; offspring born nearby mutate `1 + radiation` times as often
+    #[serde(default)]
+    pub radiation: f32,
 }
 
 impl Tile {
@@ -155,6 +266,8 @@ impl Tile {
             tile_type: TileType::Empty,
             resource_amount: 0,
             max_resource: 0,
+            meat_amount: 0,
+            radiation: 0.0,
         }
     }
 
@@ -163,6 +276,8 @@ impl Tile {
             tile_type: TileType::Resource,
             resource_amount: amount,
             max_resource: max,
+            meat_amount: 0,
+            radiation: 0.0,
         }
     }
 
@@ -171,6 +286,8 @@ impl Tile {
             tile_type: TileType::Obstacle,
             resource_amount: 0,
             max_resource: 0,
+            meat_amount: 0,
+            radiation: 0.0,
         }
     }
 
@@ -179,6 +296,39 @@ impl Tile {
             tile_type: TileType::Hazard,
             resource_amount: 0,
             max_resource: 0,
+            meat_amount: 0,
+            radiation: 0.0,
+        }
+    }
+
+    /// Amount of a resource kind available to eat
+    pub fn amount(&self, kind: ResourceKind) -> i32 {
+        match kind {
+            ResourceKind::Plant if self.tile_type == TileType::Resource => self.resource_amount,
+            ResourceKind::Plant => 0,
+            ResourceKind::Meat => self.meat_amount,
+        }
+    }
+
+    /// Remove up to `max` of a resource kind, returning how much was taken
+    pub fn take(&mut self, kind: ResourceKind, max: i32) -> i32 {
+        let taken = self.amount(kind).min(max).max(0);
+        match kind {
+            ResourceKind::Plant => self.resource_amount -= taken,
+            ResourceKind::Meat => self.meat_amount -= taken,
+        }
+        taken
+    }
+
+    /// Advance plant and meat stocks by one tick
+    pub fn update_resources(&mut self, plant: &ResourceKindConfig, meat: &ResourceKindConfig) {
+        if self.tile_type == TileType::Resource {
+            self.resource_amount = plant
+                .curve
+                .apply(self.resource_amount, self.max_resource, plant.rate);
+        }
+        if self.meat_amount > 0 {
+            self.meat_amount = meat.curve.apply(self.meat_amount, self.max_resource, meat.rate);
         }
     }
 
@@ -195,6 +345,7 @@ impl Tile {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::RegenCurve;
 
     #[test]
     fn test_position_wrap() {
@@ -211,6 +362,30 @@ mod tests {
         assert_eq!(wrapped, Position::new(0, 0));
     }
 
+    #[test]
+    fn test_topologies() {
+        let corner = Position::new(0, 0);
+        let off = Position::new(-1, 0);
+        assert_eq!(Topology::Torus.resolve(off, 10, 10), Some(Position::new(9, 0)));
+        assert_eq!(Topology::Box.resolve(off, 10, 10), None);
+        assert_eq!(Topology::Cylinder.resolve(off, 10, 10), Some(Position::new(9, 0)));
+        assert_eq!(Topology::Cylinder.resolve(corner.add(0, -1), 10, 10), None);
+
+        // Six distinct hex neighbors, each one step away
+        for center in [Position::new(4, 4), Position::new(4, 5)] {
+            let neighbors: Vec<_> = Direction::all()
+                .iter()
+                .filter_map(|&d| Topology::Hex.step(center, d))
+                .collect();
+            assert_eq!(neighbors.len(), 6);
+            assert!(neighbors.iter().all(|&n| Topology::Hex.distance(center, n) == 1));
+        }
+        assert_eq!(Topology::Hex.offset(Position::new(4, 4), 0, -1), Position::new(4, 3));
+        assert_eq!(Topology::Hex.offset(Position::new(4, 5), 1, -1), Position::new(5, 4));
+        assert_eq!(Topology::Hex.distance(corner, Position::new(2, 2)), 3);
+        assert_eq!(Topology::Torus.distance(corner, Position::new(2, -3)), 3);
+    }
+
     #[test]
     fn test_manhattan_distance() {
         let pos1 = Position::new(0, 0);
@@ -226,6 +401,35 @@ mod tests {
         assert!(tile.resource_amount <= 100);
     }
 
+    #[test]
+    fn test_tile_resource_kinds() {
+        let plant = ResourceKindConfig {
+            energy_yield: 1.0,
+            curve: RegenCurve::Linear,
+            rate: 0.1,
+            bite_size: 100,
+            sense_code: 1,
+        };
+        let meat = ResourceKindConfig::meat();
+
+        let mut tile = Tile {
+            meat_amount: 150,
+            ..Tile::resource(0, 100)
+        };
+        tile.update_resources(&plant, &meat);
+        assert_eq!(tile.resource_amount, 10);
+        assert_eq!(tile.meat_amount, 149);
+
+        assert_eq!(tile.take(ResourceKind::Meat, 100), 100);
+        assert_eq!(tile.take(ResourceKind::Meat, 100), 49);
+        assert_eq!(tile.amount(ResourceKind::Meat), 0);
+
+        // Plant stock only counts on resource tiles
+        let mut empty = Tile::empty();
+        empty.resource_amount = 50;
+        assert_eq!(empty.take(ResourceKind::Plant, 100), 0);
+    }
+
     #[test]
     fn test_direction_delta() {
         assert_eq!(Direction::North.to_delta(), (0, -1));
@@ -95,9 +95,12 @@ impl Compiler {
         use wasm_encoder::Instruction as WI;
 
         // Declare local variables properly
-        // Ensure we have at least 2 temp locals for complex operations like Min/Max
+        // Ensure we have at least 2 temp locals for complex operations like Min/Max,
+        // and enough locals for every register the (possibly mutated) body uses.
+        // Local 0 is the function parameter, so register N needs N locals.
         let min_locals = 2;
-        let num_locals = func.num_locals.max(min_locals);
+        let used_locals = func.max_register().map(|r| r as usize).unwrap_or(0);
+        let num_locals = func.num_locals.max(min_locals).max(used_locals);
         let mut locals = vec![];
         if num_locals > 0 {
             locals.push((num_locals as u32, ValType::I32));
@@ -398,6 +401,33 @@ impl Compiler {
                     wasm_func.instruction(&WI::Drop);
                 }
             }
+            Opcode::EatKind => {
+                self.load_operands(wasm_func, &inst.operands)?;
+                wasm_func.instruction(&WI::Call(self.get_import_index("eat_kind")));
+                if let Some(dest) = inst.dest {
+                    wasm_func.instruction(&WI::LocalSet(dest.0 as u32));
+                } else {
+                    wasm_func.instruction(&WI::Drop);
+                }
+            }
+            Opcode::Mate => {
+                self.load_operands(wasm_func, &inst.operands)?;
+                wasm_func.instruction(&WI::Call(self.get_import_index("mate")));
+                if let Some(dest) = inst.dest {
+                    wasm_func.instruction(&WI::LocalSet(dest.0 as u32));
+                } else {
+                    wasm_func.instruction(&WI::Drop);
+                }
+            }
+            Opcode::ShareEnergy => {
+                self.load_operands(wasm_func, &inst.operands)?;
+                wasm_func.instruction(&WI::Call(self.get_import_index("share_energy")));
+                if let Some(dest) = inst.dest {
+                    wasm_func.instruction(&WI::LocalSet(dest.0 as u32));
+                } else {
+                    wasm_func.instruction(&WI::Drop);
+                }
+            }
             Opcode::SenseEnv => {
                 self.load_operands(wasm_func, &inst.operands)?;
                 wasm_func.instruction(&WI::Call(self.get_import_index("env_read")));
@@ -528,6 +558,12 @@ impl Compiler {
         types.function([], [ValType::I32]);
         // emit_signal: (i32, i32) -> void
         types.function([ValType::I32, ValType::I32], []);
+        // eat_kind: (i32) -> i32
+        types.function([ValType::I32], [ValType::I32]);
+        // mate: (i32) -> i32
+        types.function([ValType::I32], [ValType::I32]);
+        // share_energy: (i32, i32) -> i32
+        types.function([ValType::I32, ValType::I32], [ValType::I32]);
     }
 
     fn add_host_imports(&self, imports: &mut ImportSection) {
@@ -541,6 +577,9 @@ impl Compiler {
             ("sense_neighbor", 8),
             ("try_reproduce", 9),
             ("emit_signal", 10),
+            ("eat_kind", 11),
+            ("mate", 12),
+            ("share_energy", 13),
         ];
 
         for (name, type_idx) in host_imports {
@@ -549,7 +588,7 @@ impl Compiler {
     }
 
     fn num_host_imports(&self) -> u32 {
-        9 // Number of host imports
+        12 // Number of host imports
     }
 
     fn get_import_index(&self, name: &str) -> u32 {
@@ -563,6 +602,9 @@ impl Compiler {
             "sense_neighbor" => 6,
             "try_reproduce" => 7,
             "emit_signal" => 8,
+            "eat_kind" => 9,
+            "mate" => 10,
+            "share_energy" => 11,
             _ => 0,
         }
     }
@@ -648,6 +690,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compile_declares_used_registers() {
+        let compiler = Compiler::new(CompilerConfig::default());
+
+        let mut program = Program::new();
+        let mut init = Function::new("init".to_string(), 1, ReturnType::Void);
+        init.get_block_mut(0).unwrap().add_instruction(Instruction::return_void());
+        program.add_function(init);
+
+        // Mutation can introduce registers beyond num_locals
+        let mut step = Function::new("step".to_string(), 1, ReturnType::Int);
+        step.get_block_mut(0).unwrap().add_instruction(
+            Instruction::load_const(Register(15), Value::Int(7))
+        );
+        step.get_block_mut(0).unwrap().add_instruction(
+            Instruction::return_value(Register(15))
+        );
+        program.add_function(step);
+
+        let bytes = compiler.compile(&program).unwrap();
+        match wabt::wasm2wat(&bytes) {
+            Ok(_) => {},
+            Err(e) => panic!("WASM validation failed: {:?}", e),
+        }
+    }
+
     #[test]
     fn test_compile_unimplemented_opcodes() {
         use crate::instruction::{Operand};
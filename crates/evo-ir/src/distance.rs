@@ -0,0 +1,116 @@
+//! Distance between genomes.
+//!
+//! Functions are paired up by index and their instructions (blocks laid end
+//! to end) are aligned with an edit distance: inserting or deleting an
+//! instruction costs 1, replacing one costs 1, or 0.5 if only its operands
+//! or destination differ. A function without a partner costs its length.
+//! The total is divided by the summed lengths of the longer function in
+//! each pair, so distances fall between 0 (identical) and 1.
+
+use crate::instruction::Instruction;
+use crate::program::{Function, Program};
+
+/// Normalized distance between two genomes, from 0 to 1
+pub fn genome_distance(a: &Program, b: &Program) -> f64 {
+    let functions = a.functions.len().max(b.functions.len());
+    let mut cost = 0.0;
+    let mut length = 0;
+
+    for index in 0..functions {
+        let a = a.functions.get(index).map(flatten).unwrap_or_default();
+        let b = b.functions.get(index).map(flatten).unwrap_or_default();
+        cost += instruction_edit_distance(&a, &b);
+        length += a.len().max(b.len());
+    }
+
+    if length == 0 {
+        0.0
+    } else {
+        cost / length as f64
+    }
+}
+
+/// Edit distance between two instruction sequences
+pub fn instruction_edit_distance(a: &[&Instruction], b: &[&Instruction]) -> f64 {
+    // Two rows of the alignment table: `previous[j]` aligns a[..i - 1]
+    // with b[..j]
+    let mut previous: Vec<f64> = (0..=b.len()).map(|j| j as f64).collect();
+    let mut current = vec![0.0; b.len() + 1];
+
+    for (i, x) in a.iter().enumerate() {
+        current[0] = (i + 1) as f64;
+        for (j, y) in b.iter().enumerate() {
+            let replace = previous[j] + substitution_cost(x, y);
+            let delete = previous[j + 1] + 1.0;
+            let insert = current[j] + 1.0;
+            current[j + 1] = replace.min(delete).min(insert);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+fn substitution_cost(a: &Instruction, b: &Instruction) -> f64 {
+    if a == b {
+        0.0
+    } else if a.opcode == b.opcode {
+        0.5
+    } else {
+        1.0
+    }
+}
+
+fn flatten(function: &Function) -> Vec<&Instruction> {
+    function
+        .blocks
+        .iter()
+        .flat_map(|block| &block.instructions)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::{Opcode, Register, Value};
+    use crate::program::ReturnType;
+
+    fn program(instructions: Vec<Instruction>) -> Program {
+        let mut function = Function::new("step".to_string(), 1, ReturnType::Int);
+        function.get_block_mut(0).unwrap().instructions = instructions;
+        Program::with_functions(vec![function])
+    }
+
+    #[test]
+    fn test_genome_distance() {
+        let base = vec![
+            Instruction::load_const(Register(0), Value::Int(1)),
+            Instruction::new(Opcode::Eat).with_dest(Register(1)),
+            Instruction::return_value(Register(1)),
+        ];
+        let a = program(base.clone());
+        assert_eq!(genome_distance(&a, &a), 0.0);
+
+        // Changing a constant only changes operands
+        let mut tweaked = base.clone();
+        tweaked[0] = Instruction::load_const(Register(0), Value::Int(2));
+        assert_eq!(genome_distance(&a, &program(tweaked)), 0.5 / 3.0);
+
+        // An inserted instruction is one edit, not a shift of everything after it
+        let mut inserted = base.clone();
+        inserted.insert(0, Instruction::new(Opcode::Reproduce).with_dest(Register(2)));
+        assert_eq!(genome_distance(&a, &program(inserted)), 1.0 / 4.0);
+
+        // Functions without a partner count in full
+        let mut helper = Function::new("helper".to_string(), 0, ReturnType::Void);
+        helper
+            .get_block_mut(0)
+            .unwrap()
+            .add_instruction(Instruction::return_void());
+        let mut extra = a.clone();
+        extra.add_function(helper);
+        assert_eq!(genome_distance(&a, &extra), 1.0 / 4.0);
+
+        assert_eq!(genome_distance(&a, &program(Vec::new())), 1.0);
+    }
+}
@@ -89,6 +89,9 @@ pub enum Opcode {
     Attack,       // Attack neighbor
     Reproduce,    // Try to reproduce
     EmitSignal,   // Emit signal
+    EatKind,      // Eat a specific resource kind
+    Mate,         // Propose mating to a neighbor
+    ShareEnergy,  // Give energy to a neighbor
 }
 
 impl Opcode {
@@ -113,6 +116,9 @@ impl Opcode {
                 | Opcode::Attack
                 | Opcode::Reproduce
                 | Opcode::EmitSignal
+                | Opcode::EatKind
+                | Opcode::Mate
+                | Opcode::ShareEnergy
         )
     }
 
@@ -144,6 +150,9 @@ impl Opcode {
             Opcode::Attack => 2,         // slot, amount
             Opcode::Reproduce => 0,
             Opcode::EmitSignal => 2,     // channel, value
+            Opcode::EatKind => 1,        // kind
+            Opcode::Mate => 1,           // slot
+            Opcode::ShareEnergy => 2,    // slot, amount
         }
     }
 }
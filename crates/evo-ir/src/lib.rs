@@ -12,9 +12,11 @@ pub mod program;
 pub mod compiler;
 pub mod mutation;
 pub mod validation;
+pub mod distance;
 
 pub use instruction::{Instruction, Opcode, Value, Register};
 pub use program::{Program, Function, BasicBlock};
 pub use compiler::Compiler;
 pub use mutation::{Mutator, MutationConfig};
 pub use validation::validate_program;
+pub use distance::genome_distance;
@@ -26,6 +26,21 @@ pub struct MutationConfig {
     pub max_locals: usize,
 }
 
+impl MutationConfig {
+    /// Copy with every mutation probability multiplied by `factor` (capped at 1)
+    pub fn scaled(&self, factor: f32) -> Self {
+        let scale = |rate: f32| (rate * factor).clamp(0.0, 1.0);
+        Self {
+            point_mutation_rate: scale(self.point_mutation_rate),
+            insertion_rate: scale(self.insertion_rate),
+            deletion_rate: scale(self.deletion_rate),
+            block_duplication_rate: scale(self.block_duplication_rate),
+            function_addition_rate: scale(self.function_addition_rate),
+            ..self.clone()
+        }
+    }
+}
+
 impl Default for MutationConfig {
     fn default() -> Self {
         Self {
@@ -50,6 +65,10 @@ impl Mutator {
         Self { config }
     }
 
+    pub fn config(&self) -> &MutationConfig {
+        &self.config
+    }
+
     /// Mutate a program in place
     pub fn mutate(&self, program: &mut Program, rng: &mut ChaCha8Rng) {
         // Mutate each function
@@ -190,8 +209,8 @@ impl Mutator {
                     .nth(rng.gen_range(0..2))
                     .unwrap()
             }
-            // Leave action opcodes (Move, Eat, Attack, Reproduce, EmitSignal) unchanged
-            // to preserve their specific behaviors
+            // Leave action opcodes (Move, Eat, EatKind, Attack, Reproduce, Mate, ShareEnergy,
+            // EmitSignal) unchanged to preserve their specific behaviors
             _ => opcode,
         }
     }
@@ -258,6 +277,9 @@ impl Mutator {
             Opcode::GetAge,
             Opcode::Move,
             Opcode::Eat,
+            Opcode::EatKind,
+            Opcode::Mate,
+            Opcode::ShareEnergy,
             Opcode::SenseEnv,
             Opcode::SenseNeighbor,
             Opcode::Attack,
@@ -308,6 +330,19 @@ impl Mutator {
                 .with_operand(Operand::Immediate(Value::Int(rng.gen_range(-1..=1))))
                 .with_operand(Operand::Immediate(Value::Int(rng.gen_range(-1..=1))))
                 .with_dest(Register(rng.gen_range(0..8))),
+            // EatKind: 1 parameter (0 = plant, 1 = meat)
+            Opcode::EatKind => Instruction::new(opcode)
+                .with_operand(Operand::Immediate(Value::Int(rng.gen_range(0..=1))))
+                .with_dest(Register(rng.gen_range(0..8))),
+            // Mate: 1 parameter (neighbor slot)
+            Opcode::Mate => Instruction::new(opcode)
+                .with_operand(Operand::Immediate(Value::Int(rng.gen_range(0..8))))
+                .with_dest(Register(rng.gen_range(0..8))),
+            // ShareEnergy: 2 parameters (neighbor slot, amount)
+            Opcode::ShareEnergy => Instruction::new(opcode)
+                .with_operand(Operand::Immediate(Value::Int(rng.gen_range(0..8))))
+                .with_operand(Operand::Immediate(Value::Int(rng.gen_range(0..200))))
+                .with_dest(Register(rng.gen_range(0..8))),
             // Reproduce: no parameters
             Opcode::Reproduce => Instruction::new(opcode)
                 .with_dest(Register(rng.gen_range(0..8))),
@@ -393,6 +428,19 @@ mod tests {
         assert!(inst.operands.len() >= 2);
     }
 
+    #[test]
+    fn test_scaled_config() {
+        let config = MutationConfig {
+            point_mutation_rate: 0.4,
+            ..Default::default()
+        };
+        let scaled = config.scaled(3.0);
+
+        assert_eq!(scaled.point_mutation_rate, 1.0);
+        assert_eq!(scaled.insertion_rate, config.insertion_rate * 3.0);
+        assert_eq!(scaled.max_functions, config.max_functions);
+    }
+
     #[test]
     fn test_mutate_program() {
         let mutator = Mutator::new(MutationConfig {
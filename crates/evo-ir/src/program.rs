@@ -1,7 +1,7 @@
 //! Program structure for organism genomes.
 
 use serde::{Deserialize, Serialize};
-use crate::instruction::Instruction;
+use crate::instruction::{Instruction, Operand};
 
 /// A basic block is a sequence of instructions with no internal control flow
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,6 +83,21 @@ impl Function {
     pub fn instruction_count(&self) -> usize {
         self.blocks.iter().map(|b| b.len()).sum()
     }
+
+    /// Highest register index referenced by any instruction
+    pub fn max_register(&self) -> Option<u8> {
+        self.blocks
+            .iter()
+            .flat_map(|b| b.instructions.iter())
+            .flat_map(|inst| {
+                let operands = inst.operands.iter().filter_map(|op| match op {
+                    Operand::Register(reg) => Some(reg.0),
+                    _ => None,
+                });
+                inst.dest.map(|reg| reg.0).into_iter().chain(operands)
+            })
+            .max()
+    }
 }
 
 /// A complete organism program (genome)
@@ -216,6 +231,7 @@ mod tests {
         );
 
         assert_eq!(func.instruction_count(), 2);
+        assert_eq!(func.max_register(), Some(0));
 
         let mut program = Program::new();
         program.add_function(func);
@@ -1,15 +1,24 @@
 //! Execution context for organisms.
 
-use evo_core::{Position, OrganismId};
+use evo_core::{Position, OrganismId, ResourceKind};
 use std::sync::Arc;
 use parking_lot::RwLock;
 
+/// `sense_neighbor` result for an empty slot
+pub const NEIGHBOR_NONE: i32 = 0;
+/// `sense_neighbor` result for an organism of the same lineage
+pub const NEIGHBOR_KIN: i32 = 1;
+/// `sense_neighbor` result for an organism of another lineage
+pub const NEIGHBOR_OTHER: i32 = 2;
+
 /// Sensor data that can be read by the organism
 #[derive(Debug, Clone)]
 pub struct SensorData {
     pub energy: i32,
     pub age: u64,
     pub position: Position,
+    /// What occupies each of the 8 neighbor slots (`NEIGHBOR_*`)
+    pub neighbors: [i32; 8],
 }
 
 /// Actions that an organism can take
@@ -17,10 +26,15 @@ pub struct SensorData {
 pub enum Action {
     None,
     Move { dx: i32, dy: i32 },
-    Eat,
+    /// Eat a specific kind, or whatever is on the tile (meat first) if `None`
+    Eat { kind: Option<ResourceKind> },
     Attack { target_slot: i32, amount: i32 },
     Reproduce,
+    /// Propose mating to the organism in a neighbor slot
+    Mate { slot: i32 },
     EmitSignal { channel: i32, value: i32 },
+    /// Give energy to the organism in a neighbor slot
+    ShareEnergy { slot: i32, amount: i32 },
 }
 
 /// Execution context shared between the host and organism
@@ -44,6 +58,7 @@ impl OrganismContext {
                 energy: initial_energy,
                 age: 0,
                 position,
+                neighbors: [NEIGHBOR_NONE; 8],
             })),
             actions: Arc::new(RwLock::new(Vec::new())),
             environment_query,
@@ -57,6 +72,15 @@ impl OrganismContext {
         sensors.position = position;
     }
 
+    pub fn set_neighbors(&self, neighbors: [i32; 8]) {
+        self.sensors.write().neighbors = neighbors;
+    }
+
+    /// What occupies a neighbor slot (slots wrap around)
+    pub fn sense_neighbor(&self, slot: i32) -> i32 {
+        self.sensors.read().neighbors[slot.rem_euclid(8) as usize]
+    }
+
     pub fn get_energy(&self) -> i32 {
         self.sensors.read().energy
     }
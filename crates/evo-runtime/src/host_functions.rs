@@ -1,6 +1,7 @@
 //! Host function implementations for the organism ABI.
 
 use crate::context::{Action, OrganismContext};
+use evo_core::ResourceKind;
 use std::sync::Arc;
 use wasmtime::*;
 
@@ -49,10 +50,22 @@ impl HostFunctions {
         // eat: () -> i32
         linker.func_wrap("env", "eat", |mut caller: Caller<'_, Self>| {
             let host = caller.data();
-            host.context.add_action(Action::Eat);
+            host.context.add_action(Action::Eat { kind: None });
             1 // Success
         })?;
 
+        // eat_kind: (kind: i32) -> i32, 0 = plant, 1 = meat
+        linker.func_wrap("env", "eat_kind", |caller: Caller<'_, Self>, kind: i32| {
+            let host = caller.data();
+            match ResourceKind::from_code(kind) {
+                Some(kind) => {
+                    host.context.add_action(Action::Eat { kind: Some(kind) });
+                    1 // Success
+                }
+                None => 0,
+            }
+        })?;
+
         // attack: (slot: i32, amount: i32) -> i32
         linker.func_wrap(
             "env",
@@ -71,10 +84,9 @@ impl HostFunctions {
         linker.func_wrap(
             "env",
             "sense_neighbor",
-            |mut caller: Caller<'_, Self>, slot: i32| {
-                // For now, return 0 (no neighbor)
-                // TODO: Implement proper neighbor sensing
-                0
+            |caller: Caller<'_, Self>, slot: i32| {
+                let host = caller.data();
+                host.context.sense_neighbor(slot)
             },
         )?;
 
@@ -85,6 +97,24 @@ impl HostFunctions {
             1 // Success
         })?;
 
+        // mate: (slot: i32) -> i32
+        linker.func_wrap("env", "mate", |caller: Caller<'_, Self>, slot: i32| {
+            let host = caller.data();
+            host.context.add_action(Action::Mate { slot });
+            1 // Proposal recorded; the partner has to propose back this tick
+        })?;
+
+        // share_energy: (slot: i32, amount: i32) -> i32
+        linker.func_wrap(
+            "env",
+            "share_energy",
+            |caller: Caller<'_, Self>, slot: i32, amount: i32| {
+                let host = caller.data();
+                host.context.add_action(Action::ShareEnergy { slot, amount });
+                1 // Success
+            },
+        )?;
+
         // emit_signal: (channel: i32, value: i32) -> void
         linker.func_wrap(
             "env",
@@ -4,9 +4,31 @@ use crate::context::Action;
 use crate::host_functions::HostFunctions;
 use crate::RuntimeConfig;
 use evo_core::{Error, Result};
+use serde::{Deserialize, Serialize};
 use wasmtime::*;
 use tracing::instrument;
 
+const WASM_PAGE_SIZE: usize = 64 * 1024;
+
+/// Value of a WASM global (floats stored as raw bits)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GlobalValue {
+    I32(i32),
+    I64(i64),
+    F32(u32),
+    F64(u64),
+}
+
+/// Mutable state of an organism instance: exported linear memories and globals.
+///
+/// Restoring it into a fresh instance of the same module resumes execution
+/// exactly where the snapshot was taken.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceSnapshot {
+    pub memories: Vec<(String, Vec<u8>)>,
+    pub globals: Vec<(String, GlobalValue)>,
+}
+
 /// A running organism instance
 pub struct OrganismInstance {
     store: Store<HostFunctions>,
@@ -124,6 +146,85 @@ impl OrganismInstance {
     pub fn host_functions(&self) -> &HostFunctions {
         self.store.data()
     }
+
+    /// Capture linear memory and mutable globals
+    pub fn snapshot(&mut self) -> InstanceSnapshot {
+        let mut snapshot = InstanceSnapshot::default();
+
+        let exports: Vec<(String, Extern)> = self
+            .instance
+            .exports(&mut self.store)
+            .map(|export| (export.name().to_string(), export.into_extern()))
+            .collect();
+
+        for (name, export) in exports {
+            match export {
+                Extern::Memory(memory) => {
+                    let data = memory.data(&self.store).to_vec();
+                    snapshot.memories.push((name, data));
+                }
+                Extern::Global(global) => {
+                    if global.ty(&self.store).mutability() != Mutability::Var {
+                        continue;
+                    }
+                    let value = match global.get(&mut self.store) {
+                        Val::I32(v) => GlobalValue::I32(v),
+                        Val::I64(v) => GlobalValue::I64(v),
+                        Val::F32(v) => GlobalValue::F32(v),
+                        Val::F64(v) => GlobalValue::F64(v),
+                        _ => continue,
+                    };
+                    snapshot.globals.push((name, value));
+                }
+                _ => {}
+            }
+        }
+
+        snapshot
+    }
+
+    /// Restore state captured by [`snapshot`](Self::snapshot).
+    ///
+    /// The instance must come from the same module the snapshot was taken of.
+    pub fn restore(&mut self, snapshot: &InstanceSnapshot) -> Result<()> {
+        for (name, data) in &snapshot.memories {
+            let memory = self
+                .instance
+                .get_memory(&mut self.store, name)
+                .ok_or_else(|| Error::Wasm(format!("Missing memory export {}", name)))?;
+
+            let current = memory.data_size(&self.store);
+            if data.len() > current {
+                let extra_pages = (data.len() - current).div_ceil(WASM_PAGE_SIZE);
+                memory
+                    .grow(&mut self.store, extra_pages as u64)
+                    .map_err(|e| Error::Wasm(format!("Failed to grow memory: {}", e)))?;
+            }
+
+            memory
+                .write(&mut self.store, 0, data)
+                .map_err(|e| Error::Wasm(format!("Failed to restore memory: {}", e)))?;
+        }
+
+        for (name, value) in &snapshot.globals {
+            let global = self
+                .instance
+                .get_global(&mut self.store, name)
+                .ok_or_else(|| Error::Wasm(format!("Missing global export {}", name)))?;
+
+            let value = match *value {
+                GlobalValue::I32(v) => Val::I32(v),
+                GlobalValue::I64(v) => Val::I64(v),
+                GlobalValue::F32(v) => Val::F32(v),
+                GlobalValue::F64(v) => Val::F64(v),
+            };
+            global
+                .set(&mut self.store, value)
+                .map_err(|e| Error::Wasm(format!("Failed to restore global {}: {}", name, e)))?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -208,4 +309,36 @@ mod tests {
         let result = instance.step(0);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_snapshot_restore_memory() {
+        let runtime = Runtime::new(RuntimeConfig::default()).unwrap();
+        let wasm_bytes = create_simple_wasm();
+
+        let new_instance = || {
+            let context = Arc::new(OrganismContext::new(
+                OrganismId::new(),
+                1000,
+                Position::new(0, 0),
+                Arc::new(|_, _| 0),
+            ));
+            runtime
+                .instantiate(&wasm_bytes, HostFunctions::new(context))
+                .unwrap()
+        };
+
+        let mut original = new_instance();
+        let memory = original
+            .instance
+            .get_memory(&mut original.store, "memory")
+            .unwrap();
+        memory.write(&mut original.store, 100, &[1, 2, 3, 4]).unwrap();
+
+        let snapshot = original.snapshot();
+        assert_eq!(snapshot.memories.len(), 1);
+
+        let mut restored = new_instance();
+        restored.restore(&snapshot).unwrap();
+        assert_eq!(restored.snapshot(), snapshot);
+    }
 }
@@ -10,8 +10,8 @@ pub mod instance;
 pub mod context;
 
 pub use host_functions::HostFunctions;
-pub use instance::OrganismInstance;
-pub use context::OrganismContext;
+pub use instance::{InstanceSnapshot, OrganismInstance};
+pub use context::{OrganismContext, NEIGHBOR_KIN, NEIGHBOR_NONE, NEIGHBOR_OTHER};
 
 use evo_core::{Error, Result};
 use wasmtime::*;
@@ -1,13 +1,18 @@
 //! API handlers for the server.
 
 use crate::{checkpoint::CheckpointManager, database::Database, evolution::EvolutionEngine, job_manager::JobManager};
+use crate::archive::ArchiveStats;
+use crate::hall_of_fame::{BenchmarkRecord, HallOfFameEntry};
+use crate::novelty::NoveltyStats;
+use crate::species::SpeciesStats;
 use axum::{
-    extract::State,
+    extract::{Path, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
-use evo_core::JobConfig;
+use evo_core::{JobConfig, LineageId, PhylogenyExport};
+use evo_world::simulation::BirthRecord;
 use evo_world::IslandResult;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -84,6 +89,18 @@ pub struct StatsResponse {
     pending_jobs: usize,
     completed_jobs: usize,
     total_lineages: usize,
+    /// Lineages per deme, when the bank is split into demes
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    deme_sizes: Vec<usize>,
+    species_count: usize,
+    /// Size and age of each species, from the last selection round
+    species: Vec<SpeciesStats>,
+    /// MAP-Elites coverage and QD-score, when the archive is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive: Option<ArchiveStats>,
+    /// Novelty archive size and best sparseness, when novelty search is on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    novelty: Option<NoveltyStats>,
 }
 
 /// Get server statistics
@@ -91,15 +108,63 @@ pub struct StatsResponse {
 pub async fn get_stats(State(state): State<AppState>) -> Result<Json<StatsResponse>, ApiError> {
     let stats = state.job_manager.get_stats().await;
     let total_lineages = state.db.count_lineages().await?;
+    let deme_sizes = state.evolution.deme_sizes().await?;
+    let species = state.evolution.species_stats();
+    let archive = state.evolution.archive_stats();
+    let novelty = state.evolution.novelty_stats();
 
     Ok(Json(StatsResponse {
         total_jobs: stats.total_jobs,
         pending_jobs: stats.pending_jobs,
         completed_jobs: stats.completed_jobs,
         total_lineages,
+        deme_sizes,
+        species_count: species.len(),
+        species,
+        archive,
+        novelty,
     }))
 }
 
+/// Get the lineage phylogeny as a flat JSON node list
+#[instrument(skip(state))]
+pub async fn get_phylogeny(State(state): State<AppState>) -> Result<Json<PhylogenyExport>, ApiError> {
+    let phylogeny = state.evolution.phylogeny().await?;
+    Ok(Json(phylogeny.to_export()))
+}
+
+/// Get the lineage phylogeny in Newick format
+#[instrument(skip(state))]
+pub async fn get_phylogeny_newick(State(state): State<AppState>) -> Result<String, ApiError> {
+    let phylogeny = state.evolution.phylogeny().await?;
+    Ok(phylogeny.to_newick())
+}
+
+/// Get the organisms born into a lineage, with their parents
+#[instrument(skip(state))]
+pub async fn get_lineage_births(
+    State(state): State<AppState>,
+    Path(lineage_id): Path<uuid::Uuid>,
+) -> Result<Json<Vec<BirthRecord>>, ApiError> {
+    Ok(Json(state.db.get_births(LineageId(lineage_id)).await?))
+}
+
+/// Get the hall of fame, best first within each objective
+#[instrument(skip(state))]
+pub async fn get_hall_of_fame(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<HallOfFameEntry>>, ApiError> {
+    Ok(Json(state.db.get_hall_of_fame().await?))
+}
+
+/// Get every benchmark result, oldest first
+#[instrument(skip(state))]
+pub async fn get_benchmarks(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<BenchmarkRecord>>, ApiError> {
+    Ok(Json(state.db.get_benchmarks().await?))
+}
+
 /// Get current job configuration
 #[instrument(skip(state))]
 pub async fn get_config(State(state): State<AppState>) -> Json<JobConfig> {
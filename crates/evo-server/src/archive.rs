@@ -0,0 +1,175 @@
+//! MAP-Elites archive of genomes.
+//!
+//! Survivors are placed in a grid cell by their behavior descriptors and
+//! replace the cell's elite if they are fitter. Jobs can then sample elites
+//! uniformly over the filled cells, so rare behaviors keep getting tried
+//! even when they don't rank well overall.
+
+use evo_core::{ArchiveAxis, FitnessMetrics, LineageId};
+use evo_ir::Program;
+use rand::seq::IteratorRandom;
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// The best genome found for one niche
+#[derive(Debug, Clone)]
+pub struct Elite {
+    pub lineage_id: LineageId,
+    pub fitness: f64,
+    pub genome: Program,
+}
+
+/// Archive summary for monitoring
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArchiveStats {
+    /// Filled cells
+    pub filled: usize,
+    /// Fraction of cells that are filled
+    pub coverage: f64,
+    /// Sum of the elites' fitness
+    pub qd_score: f64,
+    /// Fitness of the best elite
+    pub best_fitness: f64,
+}
+
+#[derive(Debug)]
+pub struct Archive {
+    axes: Vec<ArchiveAxis>,
+    /// Elites by cell coordinates, ordered so sampling is reproducible
+    cells: BTreeMap<Vec<u32>, Elite>,
+}
+
+impl Archive {
+    pub fn new(axes: Vec<ArchiveAxis>) -> Self {
+        Self {
+            axes,
+            cells: BTreeMap::new(),
+        }
+    }
+
+    /// Cell coordinates of a behavior
+    pub fn cell_of(&self, metrics: &FitnessMetrics) -> Vec<u32> {
+        self.axes
+            .iter()
+            .map(|axis| axis.bin(axis.descriptor.value(metrics)))
+            .collect()
+    }
+
+    /// Total number of cells
+    pub fn capacity(&self) -> usize {
+        self.axes.iter().map(|axis| axis.bins.max(1) as usize).product()
+    }
+
+    /// Offer a genome for its behavior's cell; returns true if it became
+    /// the cell's elite
+    pub fn insert(
+        &mut self,
+        lineage_id: LineageId,
+        metrics: &FitnessMetrics,
+        fitness: f64,
+        genome: &Program,
+    ) -> bool {
+        let cell = self.cell_of(metrics);
+        if self.cells.get(&cell).is_some_and(|elite| elite.fitness >= fitness) {
+            return false;
+        }
+        self.cells.insert(
+            cell,
+            Elite {
+                lineage_id,
+                fitness,
+                genome: genome.clone(),
+            },
+        );
+        true
+    }
+
+    /// Up to `count` elites from distinct cells, chosen uniformly
+    pub fn sample(&self, count: usize, rng: &mut ChaCha8Rng) -> Vec<&Elite> {
+        self.cells.values().choose_multiple(rng, count)
+    }
+
+    pub fn stats(&self) -> ArchiveStats {
+        let filled = self.cells.len();
+        ArchiveStats {
+            filled,
+            coverage: filled as f64 / self.capacity() as f64,
+            qd_score: self.cells.values().map(|elite| elite.fitness).sum(),
+            best_fitness: self
+                .cells
+                .values()
+                .map(|elite| elite.fitness)
+                .fold(0.0, f64::max),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evo_core::BehaviorDescriptor;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    fn archive() -> Archive {
+        Archive::new(vec![
+            ArchiveAxis {
+                descriptor: BehaviorDescriptor::Exploration,
+                max: 100.0,
+                bins: 4,
+            },
+            ArchiveAxis {
+                descriptor: BehaviorDescriptor::Diet,
+                max: 1.0,
+                bins: 2,
+            },
+        ])
+    }
+
+    fn metrics(tiles_explored: u32, meat_eaten: i64) -> FitnessMetrics {
+        FitnessMetrics {
+            tiles_explored,
+            plant_eaten: 10,
+            meat_eaten,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cells_keep_the_fittest() {
+        let mut archive = archive();
+        let genome = Program::new();
+
+        assert_eq!(archive.cell_of(&metrics(30, 0)), [1, 0]);
+        // Out of range values go to the last bin
+        assert_eq!(archive.cell_of(&metrics(500, 90)), [3, 1]);
+
+        let (weak, strong) = (LineageId::new(), LineageId::new());
+        assert!(archive.insert(weak, &metrics(30, 0), 5.0, &genome));
+        assert!(archive.insert(strong, &metrics(40, 0), 8.0, &genome));
+        assert!(!archive.insert(weak, &metrics(26, 0), 6.0, &genome));
+        assert!(archive.insert(weak, &metrics(90, 90), 1.0, &genome));
+
+        let stats = archive.stats();
+        assert_eq!(stats.filled, 2);
+        assert_eq!(stats.coverage, 2.0 / 8.0);
+        assert_eq!(stats.qd_score, 9.0);
+        assert_eq!(stats.best_fitness, 8.0);
+    }
+
+    #[test]
+    fn test_sampling_draws_distinct_cells() {
+        let mut archive = archive();
+        for tiles in [0, 30, 60, 90] {
+            archive.insert(LineageId::new(), &metrics(tiles, 0), 1.0, &Program::new());
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let sample = archive.sample(3, &mut rng);
+        assert_eq!(sample.len(), 3);
+        let lineages: HashSet<_> = sample.iter().map(|elite| elite.lineage_id).collect();
+        assert_eq!(lineages.len(), 3);
+        assert_eq!(archive.sample(10, &mut rng).len(), 4);
+    }
+}
@@ -1,9 +1,12 @@
 //! Database layer for persisting genomes and statistics.
 
-use evo_core::{LineageId, Result, Error};
+use crate::hall_of_fame::{Benchmark, BenchmarkRecord, HallOfFameEntry};
+use evo_core::{JobId, LineageId, LineageRecord, Objective, OrganismId, Result, Error};
 use evo_ir::Program;
+use evo_world::simulation::BirthRecord;
 use evo_world::IslandJob;
 use sqlx::{sqlite::SqlitePool, Row};
+use std::collections::HashMap;
 use std::path::Path;
 use tracing::info;
 
@@ -71,6 +74,98 @@ impl Database {
         .await
         .map_err(|e| Error::Database(format!("Migration failed: {}", e)))?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS genealogy (
+                lineage_id TEXT PRIMARY KEY,
+                parent_ids TEXT NOT NULL,
+                generation INTEGER NOT NULL,
+                created_at INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Migration failed: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS births (
+                organism_id TEXT PRIMARY KEY,
+                job_id TEXT NOT NULL,
+                lineage_id TEXT NOT NULL,
+                parent_ids TEXT NOT NULL,
+                generation INTEGER NOT NULL,
+                tick INTEGER NOT NULL,
+                mutation_scale REAL NOT NULL,
+                created_at INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Migration failed: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS demes (
+                lineage_id TEXT PRIMARY KEY,
+                deme INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Migration failed: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS hall_of_fame (
+                objective TEXT NOT NULL,
+                lineage_id TEXT NOT NULL,
+                score REAL NOT NULL,
+                generation INTEGER NOT NULL,
+                config TEXT NOT NULL,
+                genome_data BLOB NOT NULL,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (objective, lineage_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Migration failed: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS benchmark_jobs (
+                job_id TEXT PRIMARY KEY,
+                round INTEGER NOT NULL,
+                seed INTEGER NOT NULL,
+                sides TEXT NOT NULL,
+                completed INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Migration failed: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS benchmarks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                record TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Migration failed: {}", e)))?;
+
         info!("Database migrations complete");
         Ok(())
     }
@@ -147,6 +242,385 @@ impl Database {
         Ok(count as usize)
     }
 
+    /// Record a lineage's parents and generation (first record wins)
+    pub async fn store_lineage_record(&self, record: &LineageRecord) -> Result<()> {
+        let parent_ids = serde_json::to_string(&record.parents)?;
+        let now = chrono::Utc::now().timestamp();
+
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO genealogy (lineage_id, parent_ids, generation, created_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(record.lineage_id.0.to_string())
+        .bind(parent_ids)
+        .bind(record.generation as i64)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to store lineage record: {}", e)))?;
+
+        Ok(())
+    }
+
+    pub async fn get_lineage_record(&self, lineage_id: LineageId) -> Result<Option<LineageRecord>> {
+        let row = sqlx::query(
+            "SELECT lineage_id, parent_ids, generation FROM genealogy WHERE lineage_id = ?1",
+        )
+        .bind(lineage_id.0.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to get lineage record: {}", e)))?;
+
+        row.map(|row| Self::lineage_record_from_row(&row)).transpose()
+    }
+
+    pub async fn get_genealogy(&self) -> Result<Vec<LineageRecord>> {
+        let rows = sqlx::query("SELECT lineage_id, parent_ids, generation FROM genealogy")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Failed to get genealogy: {}", e)))?;
+
+        rows.iter().map(Self::lineage_record_from_row).collect()
+    }
+
+    fn lineage_record_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<LineageRecord> {
+        let lineage_id_str: String = row.get("lineage_id");
+        let lineage_id = LineageId(
+            uuid::Uuid::parse_str(&lineage_id_str)
+                .map_err(|e| Error::Database(format!("Invalid lineage ID: {}", e)))?,
+        );
+        let parent_ids: String = row.get("parent_ids");
+        let generation: i64 = row.get("generation");
+
+        Ok(LineageRecord {
+            lineage_id,
+            parents: serde_json::from_str(&parent_ids)?,
+            generation: generation as u32,
+        })
+    }
+
+    /// Record the organisms born in a job, with their parents
+    pub async fn store_births(&self, job_id: JobId, births: &[BirthRecord]) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::Database(format!("Failed to store births: {}", e)))?;
+
+        for birth in births {
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO births
+                    (organism_id, job_id, lineage_id, parent_ids, generation, tick,
+                     mutation_scale, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                "#,
+            )
+            .bind(birth.organism_id.0.to_string())
+            .bind(job_id.0.to_string())
+            .bind(birth.lineage_id.0.to_string())
+            .bind(serde_json::to_string(&birth.parent_ids)?)
+            .bind(birth.generation as i64)
+            .bind(birth.tick as i64)
+            .bind(birth.mutation_scale as f64)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Database(format!("Failed to store births: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| Error::Database(format!("Failed to store births: {}", e)))?;
+        Ok(())
+    }
+
+    /// Organisms born into a lineage, in the order they were stored
+    pub async fn get_births(&self, lineage_id: LineageId) -> Result<Vec<BirthRecord>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT organism_id, lineage_id, parent_ids, generation, tick, mutation_scale
+            FROM births
+            WHERE lineage_id = ?1
+            ORDER BY rowid
+            "#,
+        )
+        .bind(lineage_id.0.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to get births: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                let organism_id: String = row.get("organism_id");
+                let parent_ids: String = row.get("parent_ids");
+                let generation: i64 = row.get("generation");
+                let tick: i64 = row.get("tick");
+                let mutation_scale: f64 = row.get("mutation_scale");
+                Ok(BirthRecord {
+                    organism_id: OrganismId(
+                        uuid::Uuid::parse_str(&organism_id)
+                            .map_err(|e| Error::Database(format!("Invalid organism ID: {}", e)))?,
+                    ),
+                    lineage_id,
+                    parent_ids: serde_json::from_str(&parent_ids)?,
+                    generation: generation as u32,
+                    tick: tick as u64,
+                    mutation_scale: mutation_scale as f32,
+                })
+            })
+            .collect()
+    }
+
+    /// Move a lineage into a deme
+    pub async fn set_deme(&self, lineage_id: LineageId, deme: u32) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+
+        sqlx::query(
+            r#"
+            INSERT INTO demes (lineage_id, deme, updated_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(lineage_id) DO UPDATE SET
+                deme = ?2,
+                updated_at = ?3
+            "#,
+        )
+        .bind(lineage_id.0.to_string())
+        .bind(deme as i64)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to set deme: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Deme of every lineage that has one
+    pub async fn get_demes(&self) -> Result<HashMap<LineageId, u32>> {
+        let rows = sqlx::query("SELECT lineage_id, deme FROM demes")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Failed to get demes: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                let lineage_id_str: String = row.get("lineage_id");
+                let lineage_id = LineageId(
+                    uuid::Uuid::parse_str(&lineage_id_str)
+                        .map_err(|e| Error::Database(format!("Invalid lineage ID: {}", e)))?,
+                );
+                let deme: i64 = row.get("deme");
+                Ok((lineage_id, deme as u32))
+            })
+            .collect()
+    }
+
+    /// Offer a genome to its objective's hall of fame, which keeps the
+    /// `size` best; a lineage already in it keeps its better entry
+    pub async fn offer_hall_of_fame(&self, entry: &HallOfFameEntry, size: usize) -> Result<()> {
+        let objective = objective_name(entry.objective)?;
+        let now = chrono::Utc::now().timestamp();
+
+        sqlx::query(
+            r#"
+            INSERT INTO hall_of_fame
+                (objective, lineage_id, score, generation, config, genome_data, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT(objective, lineage_id) DO UPDATE SET
+                score = ?3,
+                generation = ?4,
+                config = ?5,
+                genome_data = ?6,
+                updated_at = ?7
+            WHERE ?3 > hall_of_fame.score
+            "#,
+        )
+        .bind(&objective)
+        .bind(entry.lineage_id.0.to_string())
+        .bind(entry.score)
+        .bind(entry.generation as i64)
+        .bind(serde_json::to_string(&entry.config)?)
+        .bind(entry.genome.to_bytes()?)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to update hall of fame: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM hall_of_fame
+            WHERE objective = ?1 AND lineage_id NOT IN (
+                SELECT lineage_id FROM hall_of_fame
+                WHERE objective = ?1
+                ORDER BY score DESC
+                LIMIT ?2
+            )
+            "#,
+        )
+        .bind(&objective)
+        .bind(size as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to update hall of fame: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Every hall of fame entry, best first within each objective
+    pub async fn get_hall_of_fame(&self) -> Result<Vec<HallOfFameEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT objective, lineage_id, score, generation, config, genome_data
+            FROM hall_of_fame
+            ORDER BY objective, score DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to get hall of fame: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                let objective: String = row.get("objective");
+                let lineage_id_str: String = row.get("lineage_id");
+                let generation: i64 = row.get("generation");
+                let config: String = row.get("config");
+                let genome_bytes: Vec<u8> = row.get("genome_data");
+                Ok(HallOfFameEntry {
+                    objective: serde_json::from_value(serde_json::Value::String(objective))?,
+                    lineage_id: LineageId(
+                        uuid::Uuid::parse_str(&lineage_id_str)
+                            .map_err(|e| Error::Database(format!("Invalid lineage ID: {}", e)))?,
+                    ),
+                    score: row.get("score"),
+                    generation: generation as u32,
+                    config: serde_json::from_str(&config)?,
+                    genome: Program::from_bytes(&genome_bytes)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Mark a job as a benchmark, so its result is never bred from
+    pub async fn store_benchmark_job(&self, job_id: JobId, benchmark: &Benchmark) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+
+        sqlx::query(
+            r#"
+            INSERT INTO benchmark_jobs (job_id, round, seed, sides, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(job_id.0.to_string())
+        .bind(benchmark.round as i64)
+        .bind(benchmark.seed as i64)
+        .bind(serde_json::to_string(&benchmark.sides)?)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to store benchmark job: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// The benchmark a job was created for, and whether its result has
+    /// been recorded
+    pub async fn get_benchmark_job(&self, job_id: JobId) -> Result<Option<(Benchmark, bool)>> {
+        let row = sqlx::query(
+            "SELECT round, seed, sides, completed FROM benchmark_jobs WHERE job_id = ?1",
+        )
+        .bind(job_id.0.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to get benchmark job: {}", e)))?;
+
+        row.map(|row| {
+            let round: i64 = row.get("round");
+            let seed: i64 = row.get("seed");
+            let sides: String = row.get("sides");
+            let completed: i64 = row.get("completed");
+            let benchmark = Benchmark {
+                round: round as u32,
+                seed: seed as u64,
+                sides: serde_json::from_str(&sides)?,
+            };
+            Ok((benchmark, completed != 0))
+        })
+        .transpose()
+    }
+
+    /// Mark a benchmark job's result as recorded; returns false if it
+    /// already was
+    pub async fn complete_benchmark_job(&self, job_id: JobId) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE benchmark_jobs SET completed = 1 WHERE job_id = ?1 AND completed = 0",
+        )
+        .bind(job_id.0.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Failed to complete benchmark job: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Highest benchmark round created so far (0 before the first)
+    pub async fn last_benchmark_round(&self) -> Result<u32> {
+        let row = sqlx::query("SELECT COALESCE(MAX(round), 0) AS round FROM benchmark_jobs")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Failed to get benchmark round: {}", e)))?;
+
+        let round: i64 = row.get("round");
+        Ok(round as u32)
+    }
+
+    pub async fn store_benchmark(&self, record: &BenchmarkRecord) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+
+        sqlx::query("INSERT INTO benchmarks (record, created_at) VALUES (?1, ?2)")
+            .bind(serde_json::to_string(record)?)
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Failed to store benchmark: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Every benchmark result, oldest first
+    pub async fn get_benchmarks(&self) -> Result<Vec<BenchmarkRecord>> {
+        let rows = sqlx::query("SELECT record FROM benchmarks ORDER BY id")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Failed to get benchmarks: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                let record: String = row.get("record");
+                Ok(serde_json::from_str(&record)?)
+            })
+            .collect()
+    }
+
+    /// Look up a job created by this server
+    pub async fn get_job(&self, job_id: JobId) -> Result<Option<IslandJob>> {
+        let row = sqlx::query("SELECT job_data FROM jobs WHERE job_id = ?1")
+            .bind(job_id.0.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Failed to get job: {}", e)))?;
+
+        row.map(|row| {
+            let job_bytes: Vec<u8> = row.get("job_data");
+            bincode::deserialize(&job_bytes)
+                .map_err(|e| Error::Serialization(format!("Failed to deserialize job: {}", e)))
+        })
+        .transpose()
+    }
+
     pub async fn store_job(&self, job: &IslandJob) -> Result<()> {
         let job_bytes = bincode::serialize(job)
             .map_err(|e| Error::Serialization(format!("Failed to serialize job: {}", e)))?;
@@ -198,6 +672,14 @@ impl Database {
     }
 }
 
+/// Name an objective is stored under
+fn objective_name(objective: Objective) -> Result<String> {
+    match serde_json::to_value(objective)? {
+        serde_json::Value::String(name) => Ok(name),
+        other => Err(Error::Serialization(format!("Unexpected objective: {}", other))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,6 +737,138 @@ mod tests {
         assert_eq!(db.count_lineages().await.unwrap(), 1);
     }
 
+    #[tokio::test]
+    async fn test_genealogy_records() {
+        let db = create_test_db().await;
+
+        let founder = LineageRecord::founder(LineageId::new());
+        let child = LineageRecord::offspring(LineageId::new(), &[&founder]);
+        db.store_lineage_record(&founder).await.unwrap();
+        db.store_lineage_record(&child).await.unwrap();
+
+        // Re-storing a lineage keeps the original record
+        db.store_lineage_record(&LineageRecord::founder(child.lineage_id))
+            .await
+            .unwrap();
+
+        let retrieved = db.get_lineage_record(child.lineage_id).await.unwrap();
+        assert_eq!(retrieved, Some(child));
+        assert_eq!(db.get_genealogy().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_organism_births() {
+        let db = create_test_db().await;
+        let (lineage_id, job_id) = (LineageId::new(), JobId::new());
+        let (mother, father) = (OrganismId::new(), OrganismId::new());
+        let birth = |parent_ids: Vec<OrganismId>, generation, tick| BirthRecord {
+            organism_id: OrganismId::new(),
+            lineage_id,
+            parent_ids,
+            generation,
+            tick,
+            mutation_scale: 1.0,
+        };
+        let child = birth(vec![mother, father], 1, 10);
+        let grandchild = birth(vec![child.organism_id], 2, 25);
+
+        db.store_births(job_id, &[child.clone(), grandchild.clone()])
+            .await
+            .unwrap();
+        // Resubmitted results don't duplicate births
+        db.store_births(job_id, std::slice::from_ref(&child)).await.unwrap();
+        db.store_births(job_id, &[birth(Vec::new(), 1, 3)]).await.unwrap();
+
+        let births = db.get_births(lineage_id).await.unwrap();
+        assert_eq!(births.len(), 3);
+        assert_eq!(births[0].parent_ids, [mother, father]);
+        assert_eq!(births[1].parent_ids, [child.organism_id]);
+        assert_eq!((births[1].generation, births[1].tick), (2, 25));
+        assert!(db.get_births(LineageId::new()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deme_membership() {
+        let db = create_test_db().await;
+        let (a, b) = (LineageId::new(), LineageId::new());
+
+        db.set_deme(a, 0).await.unwrap();
+        db.set_deme(b, 1).await.unwrap();
+        // Migrants that come back move to their new deme
+        db.set_deme(a, 2).await.unwrap();
+
+        let demes = db.get_demes().await.unwrap();
+        assert_eq!(demes.len(), 2);
+        assert_eq!(demes[&a], 2);
+        assert_eq!(demes[&b], 1);
+    }
+
+    #[tokio::test]
+    async fn test_hall_of_fame_keeps_the_best() {
+        let db = create_test_db().await;
+        let entry = |objective, lineage_id, score| HallOfFameEntry {
+            objective,
+            lineage_id,
+            score,
+            generation: 3,
+            config: evo_core::JobConfig::default(),
+            genome: create_test_genome(),
+        };
+        let (a, b, c) = (LineageId::new(), LineageId::new(), LineageId::new());
+
+        db.offer_hall_of_fame(&entry(Objective::Fitness, a, 5.0), 2).await.unwrap();
+        db.offer_hall_of_fame(&entry(Objective::Fitness, b, 9.0), 2).await.unwrap();
+        db.offer_hall_of_fame(&entry(Objective::Fitness, c, 7.0), 2).await.unwrap();
+        // A worse score doesn't replace a lineage's entry
+        db.offer_hall_of_fame(&entry(Objective::Fitness, b, 1.0), 2).await.unwrap();
+        // Objectives are ranked separately
+        db.offer_hall_of_fame(&entry(Objective::Kills, a, 1.0), 2).await.unwrap();
+
+        let hall = db.get_hall_of_fame().await.unwrap();
+        let ranked: Vec<_> = hall
+            .iter()
+            .map(|entry| (entry.objective, entry.lineage_id, entry.score))
+            .collect();
+        assert_eq!(
+            ranked,
+            [
+                (Objective::Fitness, b, 9.0),
+                (Objective::Fitness, c, 7.0),
+                (Objective::Kills, a, 1.0),
+            ]
+        );
+        assert_eq!(hall[0].generation, 3);
+        assert_eq!(hall[0].genome.num_functions(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_benchmark_jobs() {
+        let db = create_test_db().await;
+        let benchmark = Benchmark {
+            round: 3,
+            seed: 11,
+            sides: HashMap::from([(LineageId::new(), crate::hall_of_fame::Side::Champions)]),
+        };
+        let job_id = JobId::new();
+
+        assert_eq!(db.last_benchmark_round().await.unwrap(), 0);
+        db.store_benchmark_job(job_id, &benchmark).await.unwrap();
+        assert_eq!(db.last_benchmark_round().await.unwrap(), 3);
+        assert_eq!(
+            db.get_benchmark_job(job_id).await.unwrap(),
+            Some((benchmark.clone(), false))
+        );
+        assert_eq!(db.get_benchmark_job(JobId::new()).await.unwrap(), None);
+
+        // Only the first result completes the job
+        assert!(db.complete_benchmark_job(job_id).await.unwrap());
+        assert!(!db.complete_benchmark_job(job_id).await.unwrap());
+        assert_eq!(
+            db.get_benchmark_job(job_id).await.unwrap(),
+            Some((benchmark, true))
+        );
+    }
+
     #[tokio::test]
     async fn test_get_all_genomes() {
         let db = create_test_db().await;
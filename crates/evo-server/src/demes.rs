@@ -0,0 +1,135 @@
+//! Island model over the genome bank.
+//!
+//! Every lineage belongs to one deme. A job draws most of its genomes from
+//! its own deme and a few migrants from the others, chosen by the migration
+//! policy. Survivors join the deme of the job they came back from, so
+//! migrants that do well settle in their new deme.
+
+use evo_core::{DemeConfig, LineageId, MigrationPolicy};
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+
+/// A lineage that could be sent to a job
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate {
+    pub lineage_id: LineageId,
+    pub deme: u32,
+    pub fitness: f64,
+}
+
+/// Deme of a lineage; lineages without one count as the first deme, and
+/// demes past the current count (after it was lowered) fold modulo it
+pub fn deme_of(config: &DemeConfig, demes: &HashMap<LineageId, u32>, lineage_id: LineageId) -> u32 {
+    demes.get(&lineage_id).copied().unwrap_or(0) % config.count.max(1)
+}
+
+/// Choose up to `amount` migrants into `deme`
+pub fn choose_migrants(
+    config: &DemeConfig,
+    deme: u32,
+    candidates: &[Candidate],
+    amount: usize,
+    rng: &mut ChaCha8Rng,
+) -> Vec<LineageId> {
+    if !config.enabled() || amount == 0 {
+        return Vec::new();
+    }
+
+    let source = match config.policy {
+        MigrationPolicy::Ring => Some((deme + config.count - 1) % config.count),
+        MigrationPolicy::Random | MigrationPolicy::EliteBroadcast => None,
+    };
+    let mut pool: Vec<&Candidate> = candidates
+        .iter()
+        .filter(|c| c.deme != deme && source.is_none_or(|source| c.deme == source))
+        .collect();
+
+    match config.policy {
+        MigrationPolicy::Random => pool
+            .choose_multiple(rng, amount)
+            .map(|c| c.lineage_id)
+            .collect(),
+        MigrationPolicy::Ring | MigrationPolicy::EliteBroadcast => {
+            pool.sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+            pool.iter().take(amount).map(|c| c.lineage_id).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    /// Three demes of three lineages; fitness is `10 * deme + rank`
+    fn candidates() -> Vec<Candidate> {
+        (0..3)
+            .flat_map(|deme| {
+                (0..3).map(move |rank| Candidate {
+                    lineage_id: LineageId::new(),
+                    deme,
+                    fitness: (10 * deme + rank) as f64,
+                })
+            })
+            .collect()
+    }
+
+    fn config(policy: MigrationPolicy) -> DemeConfig {
+        DemeConfig {
+            count: 3,
+            migration_rate: 0.2,
+            policy,
+        }
+    }
+
+    #[test]
+    fn test_migration_policies() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let candidates = candidates();
+        let fitness = |ids: Vec<LineageId>| -> Vec<f64> {
+            ids.iter()
+                .map(|id| candidates.iter().find(|c| c.lineage_id == *id).unwrap().fitness)
+                .collect()
+        };
+
+        // Deme 0 gets the best of deme 2, its predecessor in the ring
+        let ring = choose_migrants(&config(MigrationPolicy::Ring), 0, &candidates, 2, &mut rng);
+        assert_eq!(fitness(ring), [22.0, 21.0]);
+
+        // Elites come from anywhere but the receiving deme
+        let elites = choose_migrants(
+            &config(MigrationPolicy::EliteBroadcast),
+            2,
+            &candidates,
+            4,
+            &mut rng,
+        );
+        assert_eq!(fitness(elites), [12.0, 11.0, 10.0, 2.0]);
+
+        let random = choose_migrants(&config(MigrationPolicy::Random), 1, &candidates, 3, &mut rng);
+        assert_eq!(random.len(), 3);
+        assert!(fitness(random).iter().all(|f| !(10.0..20.0).contains(f)));
+    }
+
+    #[test]
+    fn test_single_deme_has_no_migrants() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let config = DemeConfig::default();
+        assert_eq!(config.migrants_per_job(10), 0);
+        assert!(choose_migrants(&config, 0, &candidates(), 5, &mut rng).is_empty());
+        assert_eq!(self::config(MigrationPolicy::Ring).migrants_per_job(10), 2);
+    }
+
+    #[test]
+    fn test_deme_lookup_defaults_and_folds() {
+        let config = config(MigrationPolicy::Ring);
+        let (settled, unsettled, stale) = (LineageId::new(), LineageId::new(), LineageId::new());
+        let demes = HashMap::from([(settled, 2), (stale, 4)]);
+
+        assert_eq!(deme_of(&config, &demes, settled), 2);
+        assert_eq!(deme_of(&config, &demes, unsettled), 0);
+        // Deme 4 no longer exists with three demes and folds into deme 1
+        assert_eq!(deme_of(&config, &demes, stale), 1);
+    }
+}
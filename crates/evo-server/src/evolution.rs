@@ -1,7 +1,15 @@
 //! Evolution engine for global selection and breeding.
 
+use crate::archive::{Archive, ArchiveStats};
 use crate::database::Database;
-use evo_core::{JobConfig, JobId, LineageId, LineageStats, Result};
+use crate::demes::{self, Candidate};
+use crate::hall_of_fame::{self, Benchmark, HallOfFameEntry, Side};
+use crate::novelty::{NoveltyArchive, NoveltyStats};
+use crate::species::{Speciation, SpeciesStats};
+use evo_core::{
+    ArchiveConfig, DemeConfig, HallOfFameConfig, JobConfig, JobId, LineageId, LineageRecord,
+    LineageStats, NoveltyConfig, Phylogeny, Result, ServerConfig, SpeciesConfig,
+};
 use evo_ir::{Mutator, MutationConfig, Program};
 use evo_world::{IslandJob, IslandResult};
 use parking_lot::RwLock;
@@ -17,16 +25,44 @@ pub struct EvolutionEngine {
     mutator: Mutator,
     lineage_stats: RwLock<HashMap<LineageId, LineageStats>>,
     rng: RwLock<ChaCha8Rng>,
+    demes: DemeConfig,
+    /// Deme the next job is created for
+    next_deme: RwLock<u32>,
+    /// Deme of each job that hasn't reported back yet
+    job_demes: RwLock<HashMap<JobId, u32>>,
+    species: SpeciesConfig,
+    speciation: RwLock<Speciation>,
+    archive_config: ArchiveConfig,
+    archive: RwLock<Archive>,
+    novelty_config: NoveltyConfig,
+    novelty_archive: RwLock<NoveltyArchive>,
+    /// Latest novelty of each lineage (its most novel survivor)
+    novelty: RwLock<HashMap<LineageId, f64>>,
+    hall_of_fame: HallOfFameConfig,
+    /// Jobs created since startup
+    jobs_created: RwLock<u64>,
 }
 
 impl EvolutionEngine {
-    pub fn new(db: Database) -> Self {
+    pub fn new(db: Database, server_config: &ServerConfig) -> Self {
         Self {
             db,
             config: RwLock::new(JobConfig::default()),
             mutator: Mutator::new(MutationConfig::default()),
             lineage_stats: RwLock::new(HashMap::new()),
             rng: RwLock::new(ChaCha8Rng::from_entropy()),
+            demes: server_config.demes.clone(),
+            next_deme: RwLock::new(0),
+            job_demes: RwLock::new(HashMap::new()),
+            species: server_config.species.clone(),
+            speciation: RwLock::new(Speciation::default()),
+            archive_config: server_config.archive.clone(),
+            archive: RwLock::new(Archive::new(server_config.archive.axes.clone())),
+            novelty_config: server_config.novelty.clone(),
+            novelty_archive: RwLock::new(NoveltyArchive::new(server_config.novelty.max_archive)),
+            novelty: RwLock::new(HashMap::new()),
+            hall_of_fame: server_config.hall_of_fame.clone(),
+            jobs_created: RwLock::new(0),
         }
     }
 
@@ -36,19 +72,122 @@ impl EvolutionEngine {
         let job_id = JobId::new();
         let config = self.config.read().clone();
 
+        // Jobs go to the demes in turn
+        let deme = {
+            let mut next = self.next_deme.write();
+            let deme = *next;
+            *next = (deme + 1) % self.demes.count.max(1);
+            deme
+        };
+
         // Select genomes for this island
-        let genomes = self.select_genomes_for_job(10).await?;
+        let genomes = self.select_genomes_for_job(deme, 10).await?;
 
         // Create job
         let job = IslandJob::new(job_id, config, genomes)?;
 
         // Store job in database
         self.db.store_job(&job).await?;
+        self.job_demes.write().insert(job_id, deme);
+        *self.jobs_created.write() += 1;
 
-        info!("Created job {:?} with {} genomes", job_id, job.genomes.len());
+        info!(
+            "Created job {:?} for deme {} with {} genomes",
+            job_id,
+            deme,
+            job.genomes.len()
+        );
         Ok(job)
     }
 
+    /// Jobs for a benchmark round, if one is due: the current champions
+    /// against the hall of fame's best, once on each benchmark seed
+    #[instrument(skip(self))]
+    pub async fn benchmark_jobs(&self) -> Result<Vec<IslandJob>> {
+        let settings = &self.hall_of_fame.benchmark;
+        let objective = match self.hall_of_fame.objectives.first() {
+            Some(objective) if self.hall_of_fame.size > 0 => *objective,
+            _ => return Ok(Vec::new()),
+        };
+        if !settings.due_after(*self.jobs_created.read()) {
+            return Ok(Vec::new());
+        }
+
+        let members: Vec<Program> = self
+            .db
+            .get_hall_of_fame()
+            .await?
+            .into_iter()
+            .filter(|entry| entry.objective == objective)
+            .take(settings.competitors)
+            .map(|entry| entry.genome)
+            .collect();
+        let champions = self.champions(settings.competitors).await?;
+        if members.is_empty() || champions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Competitors play under fresh lineages, so a champion that is also
+        // in the hall of fame shows up on both sides
+        let mut genomes = Vec::new();
+        let mut sides = HashMap::new();
+        let sided = champions
+            .into_iter()
+            .map(|genome| (Side::Champions, genome))
+            .chain(members.into_iter().map(|genome| (Side::HallOfFame, genome)));
+        for (side, genome) in sided {
+            let lineage_id = LineageId::new();
+            sides.insert(lineage_id, side);
+            genomes.push((lineage_id, genome));
+        }
+
+        let round = self.db.last_benchmark_round().await? + 1;
+        let mut jobs = Vec::new();
+        for &seed in &settings.seeds {
+            let config = JobConfig {
+                seed,
+                ..self.config.read().clone()
+            };
+            let job = IslandJob::new(JobId::new(), config, genomes.clone())?;
+            self.db.store_job(&job).await?;
+            let benchmark = Benchmark {
+                round,
+                seed,
+                sides: sides.clone(),
+            };
+            self.db.store_benchmark_job(job.job_id, &benchmark).await?;
+            jobs.push(job);
+        }
+
+        info!("Created {} jobs for benchmark round {}", jobs.len(), round);
+        Ok(jobs)
+    }
+
+    /// Genomes of the `count` fittest lineages
+    async fn champions(&self, count: usize) -> Result<Vec<Program>> {
+        let ranked: Vec<LineageId> = {
+            let cooperation_weight = self.config.read().dynamic_rules.cooperation_weight;
+            let stats = self.lineage_stats.read();
+            let mut ranked: Vec<(LineageId, f64)> = stats
+                .iter()
+                .map(|(id, stat)| (*id, stat.best_fitness.weighted_fitness(cooperation_weight)))
+                .collect();
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+            ranked.into_iter().map(|(id, _)| id).collect()
+        };
+
+        let mut champions = Vec::new();
+        for lineage_id in ranked {
+            if champions.len() == count {
+                break;
+            }
+            if let Some(genome) = self.db.get_genome(lineage_id).await? {
+                champions.push(genome);
+            }
+        }
+        Ok(champions)
+    }
+
     /// Process results from a completed job
     #[instrument(skip(self, result), fields(job_id = ?result.job_id, survivors = result.result.survivors.len()))]
     pub async fn process_result(&self, result: IslandResult) -> Result<()> {
@@ -58,6 +197,24 @@ impl EvolutionEngine {
             result.result.survivors.len()
         );
 
+        // Benchmark results are recorded once and never bred from, even
+        // when a timed-out job is submitted again or after a restart
+        if let Some((benchmark, _)) = self.db.get_benchmark_job(result.job_id).await? {
+            if !self.db.complete_benchmark_job(result.job_id).await? {
+                info!("Benchmark job {:?} was already recorded", result.job_id);
+                return Ok(());
+            }
+            let cooperation_weight = self.config.read().dynamic_rules.cooperation_weight;
+            let record = benchmark.score(&result.result, cooperation_weight);
+            info!(
+                "Benchmark round {} (seed {}): champions hold {:.0}% of survivors",
+                record.round,
+                record.seed,
+                record.champion_share * 100.0
+            );
+            return self.db.store_benchmark(&record).await;
+        }
+
         // Update lineage statistics
         {
             let mut stats = self.lineage_stats.write();
@@ -72,11 +229,64 @@ impl EvolutionEngine {
             }
         }
 
-        // Store survivors in database
+        // Offer survivors to the MAP-Elites archive
+        if self.archive_config.enabled {
+            let cooperation_weight = self.config.read().dynamic_rules.cooperation_weight;
+            let mut archive = self.archive.write();
+            for survivor in &result.result.survivors {
+                archive.insert(
+                    survivor.lineage_id,
+                    &survivor.metrics,
+                    survivor.metrics.weighted_fitness(cooperation_weight),
+                    &survivor.genome,
+                );
+            }
+        }
+
+        // Score survivors by how novel their behavior is
+        if self.novelty_config.enabled {
+            let survivors: Vec<_> = result
+                .result
+                .survivors
+                .iter()
+                .filter(|survivor| !survivor.behavior.is_empty())
+                .collect();
+            let behaviors: Vec<&[f64]> =
+                survivors.iter().map(|survivor| survivor.behavior.as_slice()).collect();
+            let scores = self.novelty_archive.write().evaluate(
+                &behaviors,
+                self.novelty_config.k,
+                self.novelty_config.archive_threshold,
+            );
+
+            let mut lineages: HashMap<LineageId, f64> = HashMap::new();
+            for (survivor, score) in survivors.iter().zip(scores) {
+                let best = lineages.entry(survivor.lineage_id).or_insert(score);
+                *best = best.max(score);
+            }
+            self.novelty.write().extend(lineages);
+        }
+
+        // Store survivors in database; they join the job's deme (unknown
+        // for jobs created before a restart)
+        let deme = self.job_demes.write().remove(&result.job_id);
         for survivor in &result.result.survivors {
             self.db.store_genome(survivor.lineage_id, &survivor.genome).await?;
+            self.db
+                .store_lineage_record(&LineageRecord::founder(survivor.lineage_id))
+                .await?;
+            if let Some(deme) = deme.filter(|_| self.demes.enabled()) {
+                self.db.set_deme(survivor.lineage_id, deme).await?;
+            }
         }
 
+        // Keep the organism-level genealogy of the run
+        self.db
+            .store_births(result.job_id, &result.result.births)
+            .await?;
+
+        self.update_hall_of_fame(&result).await?;
+
         // Perform selection and breeding if we have enough data
         let num_lineages = self.lineage_stats.read().len();
         if num_lineages >= 20 {
@@ -86,89 +296,253 @@ impl EvolutionEngine {
         Ok(())
     }
 
-    /// Select genomes for a new job
+    /// Offer the best survivors for each objective to the hall of fame
+    async fn update_hall_of_fame(&self, result: &IslandResult) -> Result<()> {
+        let size = self.hall_of_fame.size;
+        if size == 0 {
+            return Ok(());
+        }
+
+        // Entries keep the configuration of the job they scored in
+        let config = match self.db.get_job(result.job_id).await? {
+            Some(job) => job.config,
+            None => self.config.read().clone(),
+        };
+        let cooperation_weight = config.dynamic_rules.cooperation_weight;
+
+        for &objective in &self.hall_of_fame.objectives {
+            let candidates = hall_of_fame::candidates(
+                &result.result.survivors,
+                objective,
+                cooperation_weight,
+                size,
+            );
+            for (survivor, score) in candidates {
+                let entry = HallOfFameEntry {
+                    objective,
+                    lineage_id: survivor.lineage_id,
+                    score,
+                    generation: self.lineage_record(survivor.lineage_id).await?.generation,
+                    config: config.clone(),
+                    genome: survivor.genome.clone(),
+                };
+                self.db.offer_hall_of_fame(&entry, size).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Select genomes for a new job in `deme`: its own lineages plus
+    /// migrants from other demes
     #[instrument(skip(self))]
     async fn select_genomes_for_job(
         &self,
+        deme: u32,
         count: usize,
     ) -> Result<Vec<(LineageId, Program)>> {
         // Get all lineages from database
         let all_genomes = self.db.get_all_genomes().await?;
+        let demes = self.db.get_demes().await?;
+        let deme_of = |id: &LineageId| demes::deme_of(&self.demes, &demes, *id);
+        let (own, others): (Vec<_>, Vec<_>) =
+            all_genomes.into_iter().partition(|(id, _)| deme_of(id) == deme);
 
-        if all_genomes.is_empty() {
+        if own.is_empty() {
             // Bootstrap: create initial random genomes
-            return self.create_initial_genomes(count);
+            let genomes = self.create_initial_genomes(count)?;
+            for (lineage_id, _) in &genomes {
+                self.db
+                    .store_lineage_record(&LineageRecord::founder(*lineage_id))
+                    .await?;
+                if self.demes.enabled() {
+                    self.db.set_deme(*lineage_id, deme).await?;
+                }
+            }
+            return Ok(genomes);
         }
 
         // Select genomes based on fitness
+        let cooperation_weight = self.config.read().dynamic_rules.cooperation_weight;
         let stats = self.lineage_stats.read();
-        let mut scored: Vec<(LineageId, f64, Program)> = all_genomes
-            .into_iter()
-            .map(|(id, program)| {
-                let fitness = stats
-                    .get(&id)
-                    .map(|s| s.best_fitness.scalar_fitness())
-                    .unwrap_or(0.0);
-                (id, fitness, program)
+        let speciation = self.speciation.read();
+        let novelty = self.novelty.read();
+        let fitness = |id: &LineageId| {
+            if self.novelty_config.enabled {
+                return novelty.get(id).copied().unwrap_or(0.0);
+            }
+            let fitness = stats
+                .get(id)
+                .map(|s| s.best_fitness.weighted_fitness(cooperation_weight))
+                .unwrap_or(0.0);
+            speciation.shared_fitness(*id, fitness)
+        };
+        let mut rng = self.rng.write();
+
+        let candidates: Vec<Candidate> = others
+            .iter()
+            .map(|(id, _)| Candidate {
+                lineage_id: *id,
+                deme: deme_of(id),
+                fitness: fitness(id),
             })
             .collect();
+        let migrants = demes::choose_migrants(
+            &self.demes,
+            deme,
+            &candidates,
+            self.demes.migrants_per_job(count),
+            &mut rng,
+        );
+
+        let mut scored: Vec<(LineageId, f64, Program)> = own
+            .into_iter()
+            .map(|(id, program)| (id, fitness(&id), program))
+            .collect();
 
         // Sort by fitness
         scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
 
-        // Take top performers + some random ones for diversity
+        // Part of the job can come from the archive, one elite per niche
         let mut selected = Vec::new();
-        let top_count = count * 7 / 10; // 70% top performers
-        let random_count = count - top_count; // 30% random
+        let mut residents = count - migrants.len();
+        if self.archive_config.enabled {
+            let wanted =
+                (residents as f32 * self.archive_config.job_fraction.clamp(0.0, 1.0)).round();
+            for elite in self.archive.read().sample(wanted as usize, &mut rng) {
+                selected.push((elite.lineage_id, elite.genome.clone()));
+            }
+            residents -= selected.len();
+        }
+
+        // Take top performers + some random ones for diversity
+        let top_count = residents * 7 / 10; // 70% top performers
+        let random_count = residents - top_count; // 30% random
 
         for (id, _, program) in scored.iter().take(top_count) {
             selected.push((*id, program.clone()));
         }
 
         // Add random genomes for diversity
-        let mut rng = self.rng.write();
         for (id, _, program) in scored.choose_multiple(&mut *rng, random_count) {
             selected.push((*id, program.clone()));
         }
 
+        for (id, program) in others.into_iter().filter(|(id, _)| migrants.contains(id)) {
+            selected.push((id, program));
+        }
+
         Ok(selected)
     }
 
+    /// Coverage and QD-score of the MAP-Elites archive, if it's enabled
+    pub fn archive_stats(&self) -> Option<ArchiveStats> {
+        self.archive_config
+            .enabled
+            .then(|| self.archive.read().stats())
+    }
+
+    /// Novelty archive size, if novelty search is enabled
+    pub fn novelty_stats(&self) -> Option<NoveltyStats> {
+        self.novelty_config
+            .enabled
+            .then(|| self.novelty_archive.read().stats())
+    }
+
+    /// Size and age of every species (empty until the first selection
+    /// round, or without speciation)
+    pub fn species_stats(&self) -> Vec<SpeciesStats> {
+        self.speciation.read().stats()
+    }
+
+    /// Number of lineages in each deme (empty with a single deme)
+    pub async fn deme_sizes(&self) -> Result<Vec<usize>> {
+        if !self.demes.enabled() {
+            return Ok(Vec::new());
+        }
+        let mut sizes = vec![0; self.demes.count as usize];
+        for deme in self.db.get_demes().await?.into_values() {
+            if let Some(size) = sizes.get_mut(deme as usize) {
+                *size += 1;
+            }
+        }
+        Ok(sizes)
+    }
+
     /// Perform selection and breeding to create new genomes
     #[instrument(skip(self))]
     async fn perform_selection(&self) -> Result<()> {
         info!("Performing selection and breeding");
 
+        // Sort the bank into species for this round
+        if self.species.enabled() {
+            let genomes = self.db.get_all_genomes().await?;
+            self.speciation
+                .write()
+                .update(self.species.threshold, &genomes);
+        }
+
+        let cooperation_weight = self.config.read().dynamic_rules.cooperation_weight;
         let survivors = {
             let stats = self.lineage_stats.read();
+            let speciation = self.speciation.read();
+            let novelty = self.novelty.read();
+            let fitness =
+                |stat: &LineageStats| stat.best_fitness.weighted_fitness(cooperation_weight);
+            // Novelty search ranks by novelty alone
+            let shared_fitness = |id: LineageId, stat: &LineageStats| {
+                if self.novelty_config.enabled {
+                    novelty.get(&id).copied().unwrap_or(0.0)
+                } else {
+                    speciation.shared_fitness(id, fitness(stat))
+                }
+            };
 
             // Find best lineages using Pareto ranking
             let mut lineages: Vec<(LineageId, &LineageStats)> = stats.iter()
                 .map(|(id, stat)| (*id, stat))
                 .collect();
 
-            // Sort by scalar fitness for simplicity
+            // Sort by scalar fitness, shared within species
             // TODO: Implement proper Pareto ranking
             lineages.sort_by(|a, b| {
-                b.1.best_fitness
-                    .scalar_fitness()
-                    .partial_cmp(&a.1.best_fitness.scalar_fitness())
+                shared_fitness(b.0, b.1)
+                    .partial_cmp(&shared_fitness(a.0, a.1))
                     .unwrap()
             });
 
             // Keep top 50%
             let keep_count = lineages.len() / 2;
-            let survivors: Vec<LineageId> = lineages
+            let mut survivors: Vec<LineageId> = lineages
                 .iter()
                 .take(keep_count)
                 .map(|(id, _)| *id)
                 .collect();
 
+            // Young species keep their best lineage
+            for species in speciation.young(self.species.protected_generations) {
+                let best = species
+                    .members
+                    .iter()
+                    .filter_map(|id| stats.get(id).map(|stat| (*id, fitness(stat))))
+                    .max_by(|a, b| a.1.total_cmp(&b.1));
+                if let Some((id, _)) = best.filter(|(id, _)| !survivors.contains(id)) {
+                    survivors.push(id);
+                }
+            }
+
             info!("Selected {} survivors from {} lineages", survivors.len(), lineages.len());
 
             survivors
         }; // stats lock is dropped here
 
+        // Parents mate within their deme
+        let demes = if self.demes.enabled() {
+            self.db.get_demes().await?
+        } else {
+            HashMap::new()
+        };
+
         // Create offspring through mutation and crossover
         let offspring_count = 10;
 
@@ -177,10 +551,14 @@ impl EvolutionEngine {
             if survivors.len() >= 2 {
                 let (parent1_id, parent2_id) = {
                     let mut rng = self.rng.write();
-                    (
-                        survivors[(*rng).gen_range(0..survivors.len())],
-                        survivors[(*rng).gen_range(0..survivors.len())]
-                    )
+                    let parent1_id = survivors[(*rng).gen_range(0..survivors.len())];
+                    let deme = demes::deme_of(&self.demes, &demes, parent1_id);
+                    let mates: Vec<LineageId> = survivors
+                        .iter()
+                        .copied()
+                        .filter(|id| demes::deme_of(&self.demes, &demes, *id) == deme)
+                        .collect();
+                    (parent1_id, *mates.choose(&mut *rng).unwrap())
                 }; // rng lock is dropped here
 
                 if let (Ok(Some(parent1)), Ok(Some(parent2))) = (
@@ -190,15 +568,30 @@ impl EvolutionEngine {
                     // Crossover and mutate
                     let child = {
                         let mut rng = self.rng.write();
-                        let child = self.mutator.crossover(&parent1, &parent2, &mut *rng);
-                        self.mutator.mutate(&mut child.clone(), &mut *rng);
+                        let mut child = self.mutator.crossover(&parent1, &parent2, &mut *rng);
+                        self.mutator.mutate(&mut child, &mut *rng);
                         child
                     }; // rng lock is dropped here
 
-                    // Store new lineage
+                    // Store new lineage along with its parentage
                     let new_lineage_id = LineageId::new();
+                    let parent1_record = self.lineage_record(parent1_id).await?;
+                    let parent2_record = self.lineage_record(parent2_id).await?;
+                    let record = LineageRecord::offspring(
+                        new_lineage_id,
+                        &[&parent1_record, &parent2_record],
+                    );
+
                     self.db.store_genome(new_lineage_id, &child).await?;
-                    debug!("Created new lineage: {:?}", new_lineage_id);
+                    self.db.store_lineage_record(&record).await?;
+                    if self.demes.enabled() {
+                        let deme = demes::deme_of(&self.demes, &demes, parent1_id);
+                        self.db.set_deme(new_lineage_id, deme).await?;
+                    }
+                    debug!(
+                        "Created new lineage: {:?} (generation {})",
+                        new_lineage_id, record.generation
+                    );
                 }
             }
         }
@@ -206,6 +599,22 @@ impl EvolutionEngine {
         Ok(())
     }
 
+    /// Look up a lineage's genealogy, treating unknown lineages as founders
+    async fn lineage_record(&self, lineage_id: LineageId) -> Result<LineageRecord> {
+        Ok(self
+            .db
+            .get_lineage_record(lineage_id)
+            .await?
+            .unwrap_or_else(|| LineageRecord::founder(lineage_id)))
+    }
+
+    /// Build the phylogeny of every recorded lineage
+    #[instrument(skip(self))]
+    pub async fn phylogeny(&self) -> Result<Phylogeny> {
+        let records = self.db.get_genealogy().await?;
+        Ok(Phylogeny::from_records(records))
+    }
+
     /// Create initial random genomes
     #[instrument(skip(self))]
     fn create_initial_genomes(&self, count: usize) -> Result<Vec<(LineageId, Program)>> {
@@ -287,3 +696,65 @@ impl EvolutionEngine {
         info!("Configuration updated");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evo_core::{FitnessMetrics, OrganismId, Position};
+    use evo_world::organism::OrganismData;
+    use evo_world::simulation::SimulationResult;
+
+    #[tokio::test]
+    async fn test_benchmark_result_is_recorded_once() {
+        let db = Database::new(":memory:").await.unwrap();
+        db.migrate().await.unwrap();
+        let engine = EvolutionEngine::new(db.clone(), &ServerConfig::default());
+
+        let competitor = LineageId::new();
+        let job_id = JobId::new();
+        let benchmark = Benchmark {
+            round: 1,
+            seed: 5,
+            sides: HashMap::from([(competitor, Side::Champions)]),
+        };
+        db.store_benchmark_job(job_id, &benchmark).await.unwrap();
+
+        let survivor = OrganismData {
+            id: OrganismId::new(),
+            lineage_id: competitor,
+            parent_ids: Vec::new(),
+            generation: 0,
+            position: Position::new(0, 0),
+            energy: 10,
+            age: 100,
+            birth_tick: 0,
+            genome: Program::new(),
+            metrics: FitnessMetrics {
+                lifetime: 100,
+                ..Default::default()
+            },
+            behavior: Vec::new(),
+        };
+        let result = || IslandResult {
+            job_id,
+            result: SimulationResult {
+                lineage_stats: HashMap::from([(competitor, vec![survivor.metrics.clone()])]),
+                survivors: vec![survivor.clone()],
+                births: Vec::new(),
+                total_ticks: 100,
+                region_stats: Vec::new(),
+            },
+        };
+
+        // A timed-out job can be re-queued and report back twice
+        engine.process_result(result()).await.unwrap();
+        engine.process_result(result()).await.unwrap();
+
+        let records = db.get_benchmarks().await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].champions.survivors, 1);
+        assert_eq!(db.count_lineages().await.unwrap(), 0);
+        assert!(db.get_hall_of_fame().await.unwrap().is_empty());
+        assert!(engine.lineage_stats.read().is_empty());
+    }
+}
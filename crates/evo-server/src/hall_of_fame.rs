@@ -0,0 +1,231 @@
+//! Hall of fame and benchmark rounds.
+//!
+//! The hall of fame keeps the best genomes ever found for each objective in
+//! the database, along with the generation and job configuration they came
+//! from, so they survive selection and restarts. Benchmark rounds put the
+//! current champions and the hall of fame's best on the same islands with
+//! fixed seeds. If the population is really improving, the champions' share
+//! of the survivors grows from round to round instead of going back and
+//! forth.
+
+use evo_core::{JobConfig, LineageId, Objective};
+use evo_ir::Program;
+use evo_world::organism::OrganismData;
+use evo_world::simulation::SimulationResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One of the best genomes found for an objective
+#[derive(Debug, Clone, Serialize)]
+pub struct HallOfFameEntry {
+    pub objective: Objective,
+    pub lineage_id: LineageId,
+    pub score: f64,
+    /// Breeding generation of the lineage
+    pub generation: u32,
+    /// Configuration of the job the genome scored in
+    pub config: JobConfig,
+    #[serde(skip_serializing)]
+    pub genome: Program,
+}
+
+/// The best survivor of each of the `count` best lineages of a result, by
+/// `objective`
+pub fn candidates(
+    survivors: &[OrganismData],
+    objective: Objective,
+    cooperation_weight: f64,
+    count: usize,
+) -> Vec<(&OrganismData, f64)> {
+    let mut best: HashMap<LineageId, (&OrganismData, f64)> = HashMap::new();
+    for survivor in survivors {
+        let score = objective.score(&survivor.metrics, cooperation_weight);
+        let entry = best.entry(survivor.lineage_id).or_insert((survivor, score));
+        if score > entry.1 {
+            *entry = (survivor, score);
+        }
+    }
+
+    let mut candidates: Vec<_> = best.into_values().collect();
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+    candidates.truncate(count);
+    candidates
+}
+
+/// Which side of a benchmark a competitor plays on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Champions,
+    HallOfFame,
+}
+
+/// What a benchmark job was created for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Benchmark {
+    pub round: u32,
+    pub seed: u64,
+    /// Side of each competitor, by the lineage it plays as in the job
+    pub sides: HashMap<LineageId, Side>,
+}
+
+/// How one side did in a benchmark job
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SideScore {
+    /// Organisms of the side that lived during the run: one founder per
+    /// competitor plus everything born into their lineages
+    pub organisms: usize,
+    /// Organisms of the side alive at the end
+    pub survivors: usize,
+    /// Mean weighted fitness of the survivors (0 without any)
+    pub mean_fitness: f64,
+}
+
+/// Outcome of one benchmark job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRecord {
+    pub round: u32,
+    pub seed: u64,
+    pub champions: SideScore,
+    pub hall_of_fame: SideScore,
+    /// The champions' share of all survivors (0.5 when there are none)
+    pub champion_share: f64,
+}
+
+impl Benchmark {
+    pub fn score(&self, result: &SimulationResult, cooperation_weight: f64) -> BenchmarkRecord {
+        let side_score = |side: Side| {
+            let ours = |id: &LineageId| self.sides.get(id) == Some(&side);
+            let founders = self.sides.values().filter(|s| **s == side).count();
+            let births = result
+                .births
+                .iter()
+                .filter(|birth| ours(&birth.lineage_id))
+                .count();
+            // Results only hold metrics for survivors
+            let fitness: Vec<f64> = result
+                .survivors
+                .iter()
+                .filter(|survivor| ours(&survivor.lineage_id))
+                .map(|survivor| survivor.metrics.weighted_fitness(cooperation_weight))
+                .collect();
+            SideScore {
+                organisms: founders + births,
+                survivors: fitness.len(),
+                mean_fitness: if fitness.is_empty() {
+                    0.0
+                } else {
+                    fitness.iter().sum::<f64>() / fitness.len() as f64
+                },
+            }
+        };
+
+        let champions = side_score(Side::Champions);
+        let hall_of_fame = side_score(Side::HallOfFame);
+        let survivors = champions.survivors + hall_of_fame.survivors;
+        BenchmarkRecord {
+            round: self.round,
+            seed: self.seed,
+            champion_share: if survivors == 0 {
+                0.5
+            } else {
+                champions.survivors as f64 / survivors as f64
+            },
+            champions,
+            hall_of_fame,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evo_core::{FitnessMetrics, OrganismId, Position};
+    use evo_world::simulation::BirthRecord;
+
+    /// An organism alive at the end of a 100 tick run
+    fn organism(lineage_id: LineageId, lifetime: u64) -> OrganismData {
+        OrganismData {
+            id: OrganismId::new(),
+            lineage_id,
+            parent_ids: Vec::new(),
+            generation: 0,
+            position: Position::new(0, 0),
+            energy: 10,
+            age: lifetime,
+            birth_tick: 100 - lifetime,
+            genome: Program::new(),
+            metrics: FitnessMetrics {
+                lifetime,
+                ..Default::default()
+            },
+            behavior: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_candidates_are_distinct_lineages() {
+        let (a, b, c) = (LineageId::new(), LineageId::new(), LineageId::new());
+        let survivors = [
+            organism(a, 10),
+            organism(a, 50),
+            organism(b, 30),
+            organism(c, 20),
+        ];
+
+        let candidates = candidates(&survivors, Objective::Lifetime, 0.0, 2);
+        let ranked: Vec<_> = candidates
+            .iter()
+            .map(|(survivor, score)| (survivor.lineage_id, *score))
+            .collect();
+        assert_eq!(ranked, [(a, 50.0), (b, 30.0)]);
+    }
+
+    #[test]
+    fn test_benchmark_score() {
+        let (champion, member) = (LineageId::new(), LineageId::new());
+        let benchmark = Benchmark {
+            round: 2,
+            seed: 7,
+            sides: HashMap::from([(champion, Side::Champions), (member, Side::HallOfFame)]),
+        };
+        // The champion's founder had two offspring and all three survived;
+        // the member's founder had one offspring and died
+        let survivors = vec![
+            organism(champion, 100),
+            organism(champion, 40),
+            organism(champion, 10),
+            organism(member, 60),
+        ];
+        let mut lineage_stats: HashMap<LineageId, Vec<FitnessMetrics>> = HashMap::new();
+        for survivor in &survivors {
+            lineage_stats
+                .entry(survivor.lineage_id)
+                .or_default()
+                .push(survivor.metrics.clone());
+        }
+        let birth = |organism: &OrganismData| BirthRecord {
+            organism_id: organism.id,
+            lineage_id: organism.lineage_id,
+            parent_ids: vec![OrganismId::new()],
+            generation: 1,
+            tick: organism.birth_tick,
+            mutation_scale: 1.0,
+        };
+        let result = SimulationResult {
+            lineage_stats,
+            births: survivors[1..4].iter().map(birth).collect(),
+            survivors,
+            total_ticks: 100,
+            region_stats: Vec::new(),
+        };
+
+        let record = benchmark.score(&result, 0.0);
+        assert_eq!((record.round, record.seed), (2, 7));
+        assert_eq!(record.champions.organisms, 3);
+        assert_eq!(record.champions.survivors, 3);
+        assert_eq!(record.hall_of_fame.organisms, 2);
+        assert_eq!(record.hall_of_fame.survivors, 1);
+        assert_eq!(record.champion_share, 0.75);
+        assert!(record.champions.mean_fitness < record.hall_of_fame.mean_fitness);
+    }
+}
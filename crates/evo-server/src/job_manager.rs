@@ -74,6 +74,11 @@ impl JobManager {
             },
         );
 
+        // Benchmark rounds wait in the queue for the next workers
+        for benchmark in evolution.benchmark_jobs().await? {
+            self.enqueue_job(benchmark).await;
+        }
+
         debug!("Created and assigned new job: {:?}", job_id);
         Ok(job)
     }
@@ -130,6 +135,7 @@ impl JobManager {
     /// Add a job to the queue
     #[instrument(skip(self, job), fields(job_id = ?job.job_id))]
     pub async fn enqueue_job(&self, job: IslandJob) {
+        *self.total_jobs.write() += 1;
         let mut pending = self.pending_jobs.write();
         pending.push(job);
     }
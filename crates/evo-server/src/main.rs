@@ -1,10 +1,15 @@
 //! Central coordination server for Evo-WASM.
 
 mod api;
+mod archive;
 mod checkpoint;
 mod database;
+mod demes;
 mod evolution;
+mod hall_of_fame;
 mod job_manager;
+mod novelty;
+mod species;
 mod telemetry;
 
 use anyhow::Result;
@@ -43,7 +48,7 @@ async fn main() -> Result<()> {
     let job_manager = Arc::new(job_manager::JobManager::new());
 
     // Initialize evolution engine
-    let evolution = Arc::new(evolution::EvolutionEngine::new(db.clone()));
+    let evolution = Arc::new(evolution::EvolutionEngine::new(db.clone(), &config));
 
     // Initialize checkpoint manager
     let checkpoint_mgr = Arc::new(checkpoint::CheckpointManager::new(
@@ -72,6 +77,11 @@ async fn main() -> Result<()> {
         .route("/api/jobs/submit", post(|state, json| async move { api::submit_result(state, json).await }))
         .route("/api/stats", get(api::get_stats))
         .route("/api/config", get(api::get_config))
+        .route("/api/phylogeny", get(api::get_phylogeny))
+        .route("/api/phylogeny/newick", get(api::get_phylogeny_newick))
+        .route("/api/lineages/:lineage_id/births", get(api::get_lineage_births))
+        .route("/api/hall-of-fame", get(api::get_hall_of_fame))
+        .route("/api/benchmarks", get(api::get_benchmarks))
         .layer(CorsLayer::permissive())
         .layer(middleware::from_fn(extract_trace_context))
         .layer(TraceLayer::new_for_http())
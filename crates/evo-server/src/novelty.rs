@@ -0,0 +1,119 @@
+//! Novelty search over behavior characterizations.
+//!
+//! A behavior's sparseness is its mean Euclidean distance to its `k`
+//! nearest neighbors among the other behaviors of the same result and the
+//! novelty archive. Behaviors sparser than the threshold are archived, so
+//! behaviors that were novel once stop being rewarded when they come back.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Archive size and the best novelty of the last evaluation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NoveltyStats {
+    pub archive_size: usize,
+    pub best_sparseness: f64,
+}
+
+#[derive(Debug)]
+pub struct NoveltyArchive {
+    behaviors: VecDeque<Vec<f64>>,
+    max_size: usize,
+    best_sparseness: f64,
+}
+
+impl NoveltyArchive {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            behaviors: VecDeque::new(),
+            max_size,
+            best_sparseness: 0.0,
+        }
+    }
+
+    /// Sparseness of each behavior in `population`, archiving the ones
+    /// above `threshold` afterwards
+    pub fn evaluate(&mut self, population: &[&[f64]], k: usize, threshold: f64) -> Vec<f64> {
+        let scores: Vec<f64> = population
+            .iter()
+            .enumerate()
+            .map(|(index, behavior)| {
+                let others = population
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != index)
+                    .map(|(_, other)| *other)
+                    .chain(self.behaviors.iter().map(Vec::as_slice));
+                let mut distances: Vec<f64> =
+                    others.map(|other| distance(behavior, other)).collect();
+                distances.sort_by(f64::total_cmp);
+                let nearest = &distances[..k.min(distances.len())];
+                if nearest.is_empty() {
+                    0.0
+                } else {
+                    nearest.iter().sum::<f64>() / nearest.len() as f64
+                }
+            })
+            .collect();
+
+        for (behavior, score) in population.iter().zip(&scores) {
+            if *score > threshold {
+                self.behaviors.push_back(behavior.to_vec());
+            }
+        }
+        while self.behaviors.len() > self.max_size {
+            self.behaviors.pop_front();
+        }
+        self.best_sparseness = scores.iter().copied().fold(0.0, f64::max);
+
+        scores
+    }
+
+    pub fn stats(&self) -> NoveltyStats {
+        NoveltyStats {
+            archive_size: self.behaviors.len(),
+            best_sparseness: self.best_sparseness,
+        }
+    }
+}
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparseness_uses_nearest_neighbors() {
+        let mut archive = NoveltyArchive::new(10);
+        let (a, b, c) = ([0.0, 0.0], [0.0, 1.0], [3.0, 4.0]);
+
+        let scores = archive.evaluate(&[&a, &b, &c], 1, 2.0);
+        assert_eq!(scores[..2], [1.0, 1.0]);
+        // c is 4.24 from b and 5 from a
+        assert!((scores[2] - 18f64.sqrt()).abs() < 1e-9);
+        assert_eq!(archive.stats().archive_size, 1);
+
+        // The archived outlier is no longer novel
+        let scores = archive.evaluate(&[&[3.0, 4.0]], 1, 2.0);
+        assert_eq!(scores, [0.0]);
+        assert_eq!(archive.stats().best_sparseness, 0.0);
+    }
+
+    #[test]
+    fn test_archive_drops_oldest() {
+        let mut archive = NoveltyArchive::new(2);
+        let behaviors: Vec<[f64; 1]> = (0..4).map(|i| [i as f64 * 10.0]).collect();
+        let population: Vec<&[f64]> = behaviors.iter().map(|b| b.as_slice()).collect();
+
+        archive.evaluate(&population, 2, 0.0);
+        assert_eq!(archive.stats().archive_size, 2);
+        assert_eq!(archive.behaviors, [vec![20.0], vec![30.0]]);
+    }
+}
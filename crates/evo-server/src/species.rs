@@ -0,0 +1,170 @@
+//! Speciation of the genome bank.
+//!
+//! Species carry over between selection rounds. Each round every genome
+//! joins the first species whose representative is within the distance
+//! threshold, or founds a new species; species left without members go
+//! extinct. A species' first member becomes its representative for the next
+//! round.
+
+use evo_core::LineageId;
+use evo_ir::{genome_distance, Program};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A group of lineages with similar genomes
+#[derive(Debug, Clone)]
+pub struct Species {
+    pub id: u32,
+    /// Selection round the species appeared in
+    pub born: u32,
+    pub members: Vec<LineageId>,
+    representative: Program,
+}
+
+/// Size and age of a species, as reported by `/api/stats`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SpeciesStats {
+    pub id: u32,
+    pub size: usize,
+    /// Selection rounds since the species appeared
+    pub age: u32,
+}
+
+/// Species of the current selection round
+#[derive(Debug, Default)]
+pub struct Speciation {
+    species: Vec<Species>,
+    /// Index into `species` of each lineage's species
+    membership: HashMap<LineageId, usize>,
+    next_id: u32,
+    generation: u32,
+}
+
+impl Speciation {
+    /// Start a new round, sorting `genomes` into species
+    pub fn update(&mut self, threshold: f64, genomes: &[(LineageId, Program)]) {
+        self.generation += 1;
+        for species in &mut self.species {
+            species.members.clear();
+        }
+
+        for (lineage_id, genome) in genomes {
+            let existing = self
+                .species
+                .iter_mut()
+                .find(|species| genome_distance(&species.representative, genome) < threshold);
+            match existing {
+                Some(species) => species.members.push(*lineage_id),
+                None => {
+                    self.species.push(Species {
+                        id: self.next_id,
+                        born: self.generation,
+                        members: vec![*lineage_id],
+                        representative: genome.clone(),
+                    });
+                    self.next_id += 1;
+                }
+            }
+        }
+
+        self.species.retain(|species| !species.members.is_empty());
+        let genomes: HashMap<LineageId, &Program> =
+            genomes.iter().map(|(id, genome)| (*id, genome)).collect();
+        self.membership.clear();
+        for (index, species) in self.species.iter_mut().enumerate() {
+            species.representative = genomes[&species.members[0]].clone();
+            for lineage_id in &species.members {
+                self.membership.insert(*lineage_id, index);
+            }
+        }
+    }
+
+    pub fn species_of(&self, lineage_id: LineageId) -> Option<&Species> {
+        self.membership
+            .get(&lineage_id)
+            .map(|&index| &self.species[index])
+    }
+
+    /// Fitness shared with the rest of the lineage's species (unchanged
+    /// for lineages that haven't been sorted into one yet)
+    pub fn shared_fitness(&self, lineage_id: LineageId, fitness: f64) -> f64 {
+        match self.species_of(lineage_id) {
+            Some(species) => fitness / species.members.len() as f64,
+            None => fitness,
+        }
+    }
+
+    /// Species younger than `generations` selection rounds
+    pub fn young(&self, generations: u32) -> impl Iterator<Item = &Species> {
+        self.species
+            .iter()
+            .filter(move |species| self.generation - species.born < generations)
+    }
+
+    pub fn stats(&self) -> Vec<SpeciesStats> {
+        self.species
+            .iter()
+            .map(|species| SpeciesStats {
+                id: species.id,
+                size: species.members.len(),
+                age: self.generation - species.born,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evo_ir::{instruction::*, program::*};
+
+    /// A step function eating `eats` times
+    fn genome(eats: usize) -> Program {
+        let mut step = Function::new("step".to_string(), 1, ReturnType::Int);
+        let block = step.get_block_mut(0).unwrap();
+        for _ in 0..eats {
+            block.add_instruction(Instruction::new(Opcode::Eat).with_dest(Register(0)));
+        }
+        block.add_instruction(Instruction::return_value(Register(0)));
+        Program::with_functions(vec![step])
+    }
+
+    #[test]
+    fn test_similar_genomes_share_a_species() {
+        let (a, b, c) = (LineageId::new(), LineageId::new(), LineageId::new());
+        let mut speciation = Speciation::default();
+        // 10 vs 11 eats are close; 1 eat is far from both
+        speciation.update(0.3, &[(a, genome(10)), (b, genome(11)), (c, genome(1))]);
+
+        assert_eq!(speciation.species_of(a).unwrap().id, speciation.species_of(b).unwrap().id);
+        assert_ne!(speciation.species_of(a).unwrap().id, speciation.species_of(c).unwrap().id);
+
+        // Fitness is shared within a species
+        assert_eq!(speciation.shared_fitness(a, 10.0), 5.0);
+        assert_eq!(speciation.shared_fitness(c, 10.0), 10.0);
+        assert_eq!(speciation.shared_fitness(LineageId::new(), 10.0), 10.0);
+    }
+
+    #[test]
+    fn test_young_species_and_extinction() {
+        let (a, b) = (LineageId::new(), LineageId::new());
+        let mut speciation = Speciation::default();
+        speciation.update(0.3, &[(a, genome(10))]);
+        speciation.update(0.3, &[(a, genome(10)), (b, genome(1))]);
+
+        let young: Vec<u32> = speciation.young(1).map(|s| s.id).collect();
+        assert_eq!(young, [speciation.species_of(b).unwrap().id]);
+        assert_eq!(speciation.young(2).count(), 2);
+
+        // The first species dies out once its last member is gone
+        speciation.update(0.3, &[(b, genome(1))]);
+        assert_eq!(
+            speciation.stats(),
+            [SpeciesStats {
+                id: 1,
+                size: 1,
+                age: 1
+            }]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Behavior characterization of organisms, for novelty search.
+//!
+//! Every organism keeps a trace of what it did. At the end of a run the
+//! trace is summarized as a vector of values between 0 and 1:
+//!
+//! - start and end position (x, y each, as fractions of the world size)
+//! - fraction of actions of each kind
+//! - fraction of ticks spent in each zone of a 4×4 partition of the world
+//! - fraction of ticks spent on each tile type
+//!
+//! Vectors from different runs have the same layout, so the Euclidean
+//! distance between them compares behaviors.
+
+use evo_core::{Position, TileType};
+use evo_runtime::context::Action;
+use serde::{Deserialize, Serialize};
+
+/// Zones per side of the world
+const ZONES: usize = 4;
+const ACTION_KINDS: usize = 8;
+const TILE_TYPES: usize = 4;
+
+/// Length of a characterization vector
+pub const BEHAVIOR_LEN: usize = 4 + ACTION_KINDS + ZONES * ZONES + TILE_TYPES;
+
+/// What an organism did during its life
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BehaviorTrace {
+    /// Where the organism was born or spawned
+    pub start: Position,
+    /// Actions taken, by kind
+    pub actions: [u32; ACTION_KINDS],
+    /// Ticks spent in each zone, row by row
+    pub zones: [u32; ZONES * ZONES],
+    /// Ticks spent on each tile type
+    pub tiles: [u32; TILE_TYPES],
+}
+
+impl BehaviorTrace {
+    pub fn new(start: Position) -> Self {
+        Self {
+            start,
+            actions: [0; ACTION_KINDS],
+            zones: [0; ZONES * ZONES],
+            tiles: [0; TILE_TYPES],
+        }
+    }
+
+    pub fn record_action(&mut self, action: &Action) {
+        let kind = match action {
+            Action::None => 0,
+            Action::Move { .. } => 1,
+            Action::Eat { .. } => 2,
+            Action::Attack { .. } => 3,
+            Action::Reproduce => 4,
+            Action::Mate { .. } => 5,
+            Action::EmitSignal { .. } => 6,
+            Action::ShareEnergy { .. } => 7,
+        };
+        self.actions[kind] += 1;
+    }
+
+    /// Record a tick spent at `pos` on a tile of type `tile_type`
+    pub fn record_tick(&mut self, pos: Position, tile_type: TileType, width: i32, height: i32) {
+        let zone = |value: i32, size: i32| {
+            (value.max(0) as usize * ZONES / size.max(1) as usize).min(ZONES - 1)
+        };
+        self.zones[zone(pos.y, height) * ZONES + zone(pos.x, width)] += 1;
+        self.tiles[tile_type as usize] += 1;
+    }
+
+    /// Characterization vector of `BEHAVIOR_LEN` values for an organism
+    /// now at `end`
+    pub fn characterize(&self, end: Position, width: i32, height: i32) -> Vec<f64> {
+        let (width, height) = (width.max(1) as f64, height.max(1) as f64);
+
+        let mut vector = vec![
+            self.start.x as f64 / width,
+            self.start.y as f64 / height,
+            end.x as f64 / width,
+            end.y as f64 / height,
+        ];
+        vector.extend(fraction(&self.actions));
+        vector.extend(fraction(&self.zones));
+        vector.extend(fraction(&self.tiles));
+        vector
+    }
+}
+
+/// Each count as a fraction of their total
+fn fraction(counts: &[u32]) -> impl Iterator<Item = f64> + '_ {
+    let total: u32 = counts.iter().sum();
+    counts
+        .iter()
+        .map(move |&count| if total > 0 { count as f64 / total as f64 } else { 0.0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_characterization() {
+        let mut trace = BehaviorTrace::new(Position::new(0, 0));
+        trace.record_action(&Action::Move { dx: 1, dy: 0 });
+        trace.record_action(&Action::Eat { kind: None });
+        trace.record_action(&Action::Eat { kind: None });
+        trace.record_action(&Action::Reproduce);
+        trace.record_tick(Position::new(1, 1), TileType::Empty, 8, 8);
+        trace.record_tick(Position::new(7, 7), TileType::Resource, 8, 8);
+
+        let vector = trace.characterize(Position::new(4, 2), 8, 8);
+        assert_eq!(vector.len(), BEHAVIOR_LEN);
+        assert_eq!(vector[..4], [0.0, 0.0, 0.5, 0.25]);
+        // Move, eat and reproduce fractions
+        assert_eq!(vector[4..9], [0.0, 0.25, 0.5, 0.0, 0.25]);
+        // First and last zone
+        let zones = &vector[12..28];
+        assert_eq!((zones[0], zones[15]), (0.5, 0.5));
+        assert_eq!(vector[28..], [0.5, 0.5, 0.0, 0.0]);
+        assert!(vector.iter().all(|v| (0.0..=1.0).contains(v)));
+    }
+}
@@ -0,0 +1,248 @@
+//! Resolving actions organisms take at the same time.
+//!
+//! Every organism decides what to do in a tick against the same view of the
+//! world, so their intents can clash. The simulation collects them and
+//! resolves them with these rules, in this order:
+//!
+//! 1. **Attacks** land simultaneously. Damage is summed per target, and an
+//!    organism killed this tick still lands its own attacks. A kill is
+//!    credited to the attacker that dealt the target the most damage.
+//! 2. Organisms killed by an attack don't move, eat or act further.
+//! 3. **Moves** only go into passable tiles that were empty when the tick
+//!    started, so nobody can take a tile someone else is leaving. When
+//!    several organisms move to the same tile, the one with priority gets it
+//!    and the others stay put.
+//! 4. **Eating** happens after moving. Organisms eating from the same tile
+//!    take their bites in priority order until the food runs out.
+//! 5. Everything else (reproduction, mating proposals, sharing, signals) is
+//!    applied in turn order; offspring take free tiles first come, first
+//!    served.
+//!
+//! An organism moves at most once per tick (its first move counts).
+//! Priority goes to the organism with the most energy after paying for its
+//! step, with ties broken by turn order: the tick's shuffle of organisms,
+//! which is derived from the seed.
+
+use evo_core::{OrganismId, Position, ResourceKind};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// An organism competing for a tile, food or a kill
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Claimant {
+    pub id: OrganismId,
+    pub energy: i32,
+    /// Position in the tick's turn order
+    pub turn: usize,
+}
+
+impl Claimant {
+    /// Sort key: more energy first, then earlier turn
+    fn priority(&self) -> (Reverse<i32>, usize) {
+        (Reverse(self.energy), self.turn)
+    }
+}
+
+/// An organism trying to move to a tile
+#[derive(Debug, Clone, Copy)]
+pub struct MoveIntent {
+    pub claimant: Claimant,
+    pub to: Position,
+}
+
+/// An organism attacking another
+#[derive(Debug, Clone, Copy)]
+pub struct AttackIntent {
+    pub attacker: Claimant,
+    pub target: OrganismId,
+    pub damage: i32,
+}
+
+/// An organism eating from the tile it stands on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MealIntent {
+    pub claimant: Claimant,
+    pub kind: Option<ResourceKind>,
+}
+
+/// All damage one target takes this tick
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hit {
+    pub target: OrganismId,
+    pub damage: i32,
+    /// Attacker credited with the kill if the target dies
+    pub killer: OrganismId,
+}
+
+/// Moves that succeed, in turn order.
+///
+/// `is_free` says whether a tile is passable and was empty at the start of
+/// the tick.
+pub fn resolve_moves(
+    moves: &[MoveIntent],
+    is_free: impl Fn(Position) -> bool,
+) -> Vec<MoveIntent> {
+    let mut winners: HashMap<Position, MoveIntent> = HashMap::new();
+    for intent in moves.iter().filter(|m| is_free(m.to)) {
+        winners
+            .entry(intent.to)
+            .and_modify(|best| {
+                if intent.claimant.priority() < best.claimant.priority() {
+                    *best = *intent;
+                }
+            })
+            .or_insert(*intent);
+    }
+
+    let mut winners: Vec<MoveIntent> = winners.into_values().collect();
+    winners.sort_by_key(|m| m.claimant.turn);
+    winners
+}
+
+/// Sum attacks per target, in the order targets were first attacked.
+///
+/// Damage is non-negative; the kill goes to the attacker that dealt the
+/// most damage, ties going to the one with priority.
+pub fn resolve_attacks(attacks: &[AttackIntent]) -> Vec<Hit> {
+    let mut targets: Vec<OrganismId> = Vec::new();
+    let mut dealt: HashMap<(OrganismId, OrganismId), (i32, Claimant)> = HashMap::new();
+    for attack in attacks {
+        if !targets.contains(&attack.target) {
+            targets.push(attack.target);
+        }
+        dealt
+            .entry((attack.target, attack.attacker.id))
+            .or_insert((0, attack.attacker))
+            .0 += attack.damage.max(0);
+    }
+
+    targets
+        .into_iter()
+        .map(|target| {
+            let attackers = dealt.iter().filter(|((t, _), _)| *t == target);
+            let damage = attackers.clone().map(|(_, (d, _))| d).sum();
+            let killer = attackers
+                .min_by_key(|(_, (d, claimant))| (Reverse(*d), claimant.priority()))
+                .map(|(_, (_, claimant))| claimant.id)
+                .unwrap();
+            Hit {
+                target,
+                damage,
+                killer,
+            }
+        })
+        .collect()
+}
+
+/// Order in which eaters take their bites: priority first, and an
+/// organism's own meals in the order it asked for them
+pub fn meal_order(meals: &mut [MealIntent]) {
+    meals.sort_by_key(|m| m.claimant.priority());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claimant(energy: i32, turn: usize) -> Claimant {
+        Claimant {
+            id: OrganismId::new(),
+            energy,
+            turn,
+        }
+    }
+
+    #[test]
+    fn test_contested_tile_goes_to_most_energy() {
+        let target = Position::new(2, 2);
+        let (weak, strong) = (claimant(100, 0), claimant(500, 1));
+        let moves = [
+            MoveIntent { claimant: weak, to: target },
+            MoveIntent { claimant: strong, to: target },
+        ];
+
+        let winners = resolve_moves(&moves, |_| true);
+        assert_eq!(winners.len(), 1);
+        assert_eq!(winners[0].claimant, strong);
+    }
+
+    #[test]
+    fn test_energy_tie_goes_to_earlier_turn() {
+        let target = Position::new(2, 2);
+        let (late, early) = (claimant(300, 4), claimant(300, 1));
+        let moves = [
+            MoveIntent { claimant: late, to: target },
+            MoveIntent { claimant: early, to: target },
+        ];
+
+        assert_eq!(resolve_moves(&moves, |_| true)[0].claimant, early);
+    }
+
+    #[test]
+    fn test_moves_only_into_tiles_free_at_tick_start() {
+        let blocked = Position::new(1, 0);
+        let moves = [
+            MoveIntent { claimant: claimant(100, 1), to: Position::new(0, 1) },
+            MoveIntent { claimant: claimant(900, 0), to: blocked },
+        ];
+
+        let winners = resolve_moves(&moves, |pos| pos != blocked);
+        assert_eq!(winners.len(), 1);
+        assert_eq!(winners[0].to, Position::new(0, 1));
+    }
+
+    #[test]
+    fn test_attacks_are_simultaneous_and_summed() {
+        let (a, b) = (claimant(100, 0), claimant(100, 1));
+        // a and b attack each other; both hits land
+        let attacks = [
+            AttackIntent { attacker: a, target: b.id, damage: 30 },
+            AttackIntent { attacker: b, target: a.id, damage: 20 },
+            AttackIntent { attacker: a, target: b.id, damage: 15 },
+        ];
+
+        let hits = resolve_attacks(&attacks);
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].target, hits[0].damage), (b.id, 45));
+        assert_eq!((hits[1].target, hits[1].damage), (a.id, 20));
+    }
+
+    #[test]
+    fn test_kill_credited_to_biggest_hitter() {
+        let target = OrganismId::new();
+        let (small, big, tied) = (claimant(900, 0), claimant(100, 2), claimant(100, 1));
+        let attacks = [
+            AttackIntent { attacker: small, target, damage: 10 },
+            AttackIntent { attacker: big, target, damage: 40 },
+        ];
+        assert_eq!(resolve_attacks(&attacks)[0].killer, big.id);
+
+        // Equal damage: priority decides
+        let attacks = [
+            AttackIntent { attacker: big, target, damage: 40 },
+            AttackIntent { attacker: tied, target, damage: 40 },
+        ];
+        assert_eq!(resolve_attacks(&attacks)[0].killer, tied.id);
+    }
+
+    #[test]
+    fn test_meals_in_priority_order() {
+        let meal = |claimant, kind| MealIntent { claimant, kind };
+        let (hungry, rich) = (claimant(50, 0), claimant(800, 2));
+        let mut meals = [
+            meal(hungry, Some(ResourceKind::Meat)),
+            meal(rich, None),
+            meal(hungry, Some(ResourceKind::Plant)),
+        ];
+
+        meal_order(&mut meals);
+        assert_eq!(
+            meals,
+            [
+                meal(rich, None),
+                meal(hungry, Some(ResourceKind::Meat)),
+                meal(hungry, Some(ResourceKind::Plant)),
+            ]
+        );
+    }
+}
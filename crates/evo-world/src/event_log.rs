@@ -0,0 +1,527 @@
+//! Structured event log and deterministic replay.
+//!
+//! A log file starts with a magic number and format version, followed by a
+//! header holding the job config and the world state at the moment recording
+//! started. The rest of the file is one length-prefixed frame per simulated
+//! tick. All records are bincode encoded.
+//!
+//! Resource regeneration is not logged; replay re-applies it each tick with
+//! the same grid code the simulation uses, so the reconstructed grid matches
+//! the original exactly.
+
+use crate::grid::Grid;
+use crate::organism::Organism;
+use crate::render::{self, Image};
+use crate::schedule;
+use evo_core::{Error, JobConfig, LineageId, OrganismId, Position, ResourceKind, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+/// Magic bytes at the start of every event log
+pub const EVENT_LOG_MAGIC: [u8; 8] = *b"EVOLOG\0\0";
+
+/// Current event log format version
+pub const EVENT_LOG_VERSION: u16 = 9;
+
+/// Something that happened to an organism during a tick
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SimEvent {
+    /// Organism placed into the world from outside (founders, migrants)
+    Spawn {
+        organism_id: OrganismId,
+        lineage_id: LineageId,
+        position: Position,
+        energy: i32,
+    },
+    Move {
+        organism_id: OrganismId,
+        to: Position,
+        energy: i32,
+    },
+    Eat {
+        organism_id: OrganismId,
+        position: Position,
+        kind: ResourceKind,
+        /// Resource removed from the tile
+        consumed: i32,
+        energy: i32,
+    },
+    Attack {
+        attacker_id: OrganismId,
+        target_id: OrganismId,
+        damage: i32,
+        attacker_energy: i32,
+        target_energy: i32,
+    },
+    ShareEnergy {
+        giver_id: OrganismId,
+        receiver_id: OrganismId,
+        /// Energy given up, before transfer loss
+        amount: i32,
+        giver_energy: i32,
+        receiver_energy: i32,
+    },
+    Birth {
+        organism_id: OrganismId,
+        lineage_id: LineageId,
+        parent_ids: Vec<OrganismId>,
+        position: Position,
+        energy: i32,
+    },
+    Death {
+        organism_id: OrganismId,
+        /// Meat left on the organism's tile
+        carcass: i32,
+    },
+    Signal {
+        organism_id: OrganismId,
+        channel: i32,
+        value: i32,
+    },
+    /// Organism moved to another region
+    Migrate {
+        organism_id: OrganismId,
+        to: Position,
+    },
+}
+
+/// All events recorded during one tick, in the order they were applied
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TickFrame {
+    pub tick: u64,
+    pub events: Vec<SimEvent>,
+}
+
+/// Organism state as far as the event log can reconstruct it.
+///
+/// Energy is the value after the organism's most recent logged event; costs
+/// that don't produce events (basal, instruction) are not reflected until
+/// the next one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayOrganism {
+    pub id: OrganismId,
+    pub lineage_id: LineageId,
+    pub position: Position,
+    pub energy: i32,
+    pub birth_tick: u64,
+}
+
+impl From<&Organism> for ReplayOrganism {
+    fn from(org: &Organism) -> Self {
+        Self {
+            id: org.id,
+            lineage_id: org.lineage_id,
+            position: org.position,
+            energy: org.energy,
+            birth_tick: org.birth_tick,
+        }
+    }
+}
+
+/// Log header: everything needed to start replaying
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLogHeader {
+    pub config: JobConfig,
+    /// Tick of the first frame in the log
+    pub start_tick: u64,
+    pub grid: Grid,
+    pub organisms: Vec<ReplayOrganism>,
+}
+
+/// Writes an event log frame by frame
+pub struct EventLogWriter {
+    writer: Box<dyn Write + Send>,
+    current: TickFrame,
+}
+
+impl EventLogWriter {
+    /// Write the magic, version and header to `writer`
+    pub fn new(writer: impl Write + Send + 'static, header: &EventLogHeader) -> Result<Self> {
+        let mut writer: Box<dyn Write + Send> = Box::new(writer);
+        writer.write_all(&EVENT_LOG_MAGIC)?;
+        writer.write_all(&EVENT_LOG_VERSION.to_le_bytes())?;
+        write_record(&mut writer, header)?;
+
+        Ok(Self {
+            writer,
+            current: TickFrame {
+                tick: header.start_tick,
+                events: Vec::new(),
+            },
+        })
+    }
+
+    /// Create a log file at `path`
+    pub fn create(path: impl AsRef<Path>, header: &EventLogHeader) -> Result<Self> {
+        let file = File::create(path)?;
+        Self::new(BufWriter::new(file), header)
+    }
+
+    /// Start collecting events for a new tick
+    pub fn begin_tick(&mut self, tick: u64) {
+        self.current.tick = tick;
+        self.current.events.clear();
+    }
+
+    pub fn record(&mut self, event: SimEvent) {
+        self.current.events.push(event);
+    }
+
+    /// Write the events collected since `begin_tick` as one frame
+    pub fn end_tick(&mut self) -> Result<()> {
+        write_record(&mut self.writer, &self.current)?;
+        self.current.events.clear();
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+fn write_record<T: Serialize>(writer: &mut impl Write, record: &T) -> Result<()> {
+    let bytes = bincode::serialize(record)?;
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| Error::Serialization("Event log record too large".to_string()))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Read the next record, or `None` at a clean end of file
+fn read_record<T: for<'de> Deserialize<'de>>(reader: &mut impl Read) -> Result<Option<T>> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    let mut bytes = vec![0u8; u32::from_le_bytes(len) as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(Some(bincode::deserialize(&bytes)?))
+}
+
+/// World state reconstructed from an event log
+#[derive(Debug, Clone)]
+pub struct WorldState {
+    /// Last tick applied (the header's start state counts as before it)
+    pub tick: Option<u64>,
+    pub grid: Grid,
+    pub organisms: HashMap<OrganismId, ReplayOrganism>,
+    /// Job config as changed by the environment schedule so far
+    config: JobConfig,
+}
+
+impl WorldState {
+    fn from_header(header: &EventLogHeader) -> Self {
+        Self {
+            tick: None,
+            grid: header.grid.clone(),
+            organisms: header
+                .organisms
+                .iter()
+                .map(|o| (o.id, o.clone()))
+                .collect(),
+            config: header.config.clone(),
+        }
+    }
+
+    /// Advance the state by one recorded tick
+    pub fn apply_frame(&mut self, frame: &TickFrame) {
+        if !self.config.schedule.is_empty() {
+            let occupied: HashSet<Position> =
+                self.organisms.values().map(|org| org.position).collect();
+            schedule::apply(&mut self.config, &mut self.grid, frame.tick, |pos| {
+                occupied.contains(&pos)
+            });
+        }
+
+        let plant = self.config.resource_kind(ResourceKind::Plant);
+        let meat = self.config.resource_kind(ResourceKind::Meat);
+        self.grid.update_resources(&plant, &meat);
+
+        for event in &frame.events {
+            self.apply_event(event, frame.tick);
+        }
+
+        self.tick = Some(frame.tick);
+    }
+
+    fn apply_event(&mut self, event: &SimEvent, tick: u64) {
+        match event {
+            SimEvent::Spawn {
+                organism_id,
+                lineage_id,
+                position,
+                energy,
+            } => {
+                self.organisms.insert(
+                    *organism_id,
+                    ReplayOrganism {
+                        id: *organism_id,
+                        lineage_id: *lineage_id,
+                        position: *position,
+                        energy: *energy,
+                        birth_tick: tick,
+                    },
+                );
+            }
+            SimEvent::Birth {
+                organism_id,
+                lineage_id,
+                position,
+                energy,
+                ..
+            } => {
+                self.organisms.insert(
+                    *organism_id,
+                    ReplayOrganism {
+                        id: *organism_id,
+                        lineage_id: *lineage_id,
+                        position: *position,
+                        energy: *energy,
+                        birth_tick: tick,
+                    },
+                );
+            }
+            SimEvent::Move {
+                organism_id,
+                to,
+                energy,
+            } => {
+                if let Some(org) = self.organisms.get_mut(organism_id) {
+                    org.position = *to;
+                    org.energy = *energy;
+                }
+            }
+            SimEvent::Migrate { organism_id, to } => {
+                if let Some(org) = self.organisms.get_mut(organism_id) {
+                    org.position = *to;
+                }
+            }
+            SimEvent::Eat {
+                organism_id,
+                position,
+                kind,
+                consumed,
+                energy,
+            } => {
+                self.grid.get_mut(*position).take(*kind, *consumed);
+                if let Some(org) = self.organisms.get_mut(organism_id) {
+                    org.energy = *energy;
+                }
+            }
+            SimEvent::Attack {
+                attacker_id,
+                target_id,
+                attacker_energy,
+                target_energy,
+                ..
+            } => {
+                if let Some(org) = self.organisms.get_mut(attacker_id) {
+                    org.energy = *attacker_energy;
+                }
+                if let Some(org) = self.organisms.get_mut(target_id) {
+                    org.energy = *target_energy;
+                }
+            }
+            SimEvent::ShareEnergy {
+                giver_id,
+                receiver_id,
+                giver_energy,
+                receiver_energy,
+                ..
+            } => {
+                if let Some(org) = self.organisms.get_mut(giver_id) {
+                    org.energy = *giver_energy;
+                }
+                if let Some(org) = self.organisms.get_mut(receiver_id) {
+                    org.energy = *receiver_energy;
+                }
+            }
+            SimEvent::Death {
+                organism_id,
+                carcass,
+            } => {
+                if let Some(org) = self.organisms.remove(organism_id) {
+                    self.grid.get_mut(org.position).meat_amount += carcass;
+                }
+            }
+            SimEvent::Signal { .. } => {}
+        }
+    }
+
+    /// Render the reconstructed world with organisms colored by lineage
+    pub fn render(&self, scale: u32) -> Image {
+        render::render(
+            &self.grid,
+            self.organisms.values().map(|o| (o.position, o.lineage_id)),
+            scale,
+        )
+    }
+
+    /// Number of living organisms per lineage
+    pub fn lineage_counts(&self) -> HashMap<LineageId, usize> {
+        let mut counts = HashMap::new();
+        for org in self.organisms.values() {
+            *counts.entry(org.lineage_id).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+/// A fully loaded event log
+pub struct Replay {
+    pub header: EventLogHeader,
+    frames: Vec<TickFrame>,
+}
+
+impl Replay {
+    /// Read an event log from any reader
+    pub fn from_reader(mut reader: impl Read) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != EVENT_LOG_MAGIC {
+            return Err(Error::Validation("Not an event log".to_string()));
+        }
+
+        let mut version = [0u8; 2];
+        reader.read_exact(&mut version)?;
+        let version = u16::from_le_bytes(version);
+        if version != EVENT_LOG_VERSION {
+            return Err(Error::Validation(format!(
+                "Unsupported event log version {} (expected {})",
+                version, EVENT_LOG_VERSION
+            )));
+        }
+
+        let header: EventLogHeader = read_record(&mut reader)?
+            .ok_or_else(|| Error::Validation("Event log has no header".to_string()))?;
+
+        let mut frames = Vec::new();
+        while let Some(frame) = read_record::<TickFrame>(&mut reader)? {
+            frames.push(frame);
+        }
+
+        Ok(Self { header, frames })
+    }
+
+    /// Open an event log file
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path)?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    pub fn frames(&self) -> &[TickFrame] {
+        &self.frames
+    }
+
+    /// Last tick recorded in the log
+    pub fn last_tick(&self) -> Option<u64> {
+        self.frames.last().map(|f| f.tick)
+    }
+
+    /// World state before the first recorded tick
+    pub fn initial_state(&self) -> WorldState {
+        WorldState::from_header(&self.header)
+    }
+
+    /// World state at the end of `tick`.
+    ///
+    /// Ticks before the first frame return the initial state; ticks after
+    /// the last frame return the final state.
+    pub fn state_at(&self, tick: u64) -> WorldState {
+        let mut state = self.initial_state();
+        for frame in self.frames.iter().take_while(|f| f.tick <= tick) {
+            state.apply_frame(frame);
+        }
+        state
+    }
+
+    /// Iterate over the world state after each recorded tick
+    pub fn states(&self) -> impl Iterator<Item = WorldState> + '_ {
+        let mut state = self.initial_state();
+        self.frames.iter().map(move |frame| {
+            state.apply_frame(frame);
+            state.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_header() -> EventLogHeader {
+        EventLogHeader {
+            config: JobConfig::default(),
+            start_tick: 0,
+            grid: Grid::new(8, 8),
+            organisms: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&EVENT_LOG_MAGIC);
+        bytes.extend_from_slice(&(EVENT_LOG_VERSION + 1).to_le_bytes());
+        write_record(&mut bytes, &test_header()).unwrap();
+
+        assert!(Replay::from_reader(bytes.as_slice()).is_err());
+        assert!(Replay::from_reader(&b"not a log at all"[..]).is_err());
+    }
+
+    #[test]
+    fn test_state_at_tick() {
+        let organism_id = OrganismId::new();
+        let lineage_id = LineageId::new();
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&EVENT_LOG_MAGIC);
+        bytes.extend_from_slice(&EVENT_LOG_VERSION.to_le_bytes());
+        write_record(&mut bytes, &test_header()).unwrap();
+
+        let frames = [
+            vec![SimEvent::Spawn {
+                organism_id,
+                lineage_id,
+                position: Position::new(1, 1),
+                energy: 100,
+            }],
+            vec![SimEvent::Move {
+                organism_id,
+                to: Position::new(2, 1),
+                energy: 90,
+            }],
+            vec![SimEvent::Death {
+                organism_id,
+                carcass: 0,
+            }],
+        ];
+        for (tick, events) in frames.into_iter().enumerate() {
+            let frame = TickFrame {
+                tick: tick as u64,
+                events,
+            };
+            write_record(&mut bytes, &frame).unwrap();
+        }
+
+        let replay = Replay::from_reader(bytes.as_slice()).unwrap();
+        assert!(replay.initial_state().organisms.is_empty());
+        assert_eq!(replay.state_at(0).organisms[&organism_id].position, Position::new(1, 1));
+
+        let moved = replay.state_at(1);
+        assert_eq!(moved.tick, Some(1));
+        assert_eq!(moved.organisms[&organism_id].position, Position::new(2, 1));
+        assert_eq!(moved.organisms[&organism_id].energy, 90);
+
+        assert!(replay.state_at(2).organisms.is_empty());
+        assert_eq!(replay.states().count(), 3);
+    }
+}
@@ -1,16 +1,80 @@
 //! 2D grid for the world.
 
-use evo_core::{Position, Tile, TileType, WorldConfig};
+use evo_core::{
+    Direction, OrganismId, Position, ResourceKindConfig, Tile, TileType, Topology, WorldConfig,
+};
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
 
-/// A 2D toroidal grid
+/// What lies past the edge of a bounded world
+static WALL: LazyLock<Tile> = LazyLock::new(Tile::obstacle);
+
+/// A 2D grid whose edges connect according to its topology
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Grid {
     pub width: i32,
     pub height: i32,
     tiles: Vec<Tile>,
+    #[serde(default)]
+    pub topology: Topology,
+    /// Organism on each tile, indexed like `tiles`. Kept up to date by the
+    /// simulation and not serialized; empty until something is placed.
+    #[serde(skip)]
+    occupants: Vec<Option<OrganismId>>,
+}
+
+/// A tile together with its position and occupant
+#[derive(Debug, Clone, Copy)]
+pub struct Cell<'a> {
+    /// Wrapped position of the tile
+    pub pos: Position,
+    pub tile: &'a Tile,
+    pub occupant: Option<OrganismId>,
+}
+
+impl Cell<'_> {
+    /// Passable and empty
+    pub fn is_free(&self) -> bool {
+        self.tile.tile_type != TileType::Obstacle && self.occupant.is_none()
+    }
+}
+
+/// Iterator over the tiles within a radius of a center (excluding it), row
+/// by row. Distance and edges follow the grid's topology; tiles past a wall
+/// are skipped, and on wrapping grids radii of half the grid or more visit
+/// some tiles twice.
+pub struct Neighborhood<'a> {
+    grid: &'a Grid,
+    center: Position,
+    radius: i32,
+    /// Column offsets to scan; hex rows are shifted, so they need one more
+    max_dx: i32,
+    dx: i32,
+    dy: i32,
+}
+
+impl<'a> Iterator for Neighborhood<'a> {
+    type Item = Cell<'a>;
+
+    fn next(&mut self) -> Option<Cell<'a>> {
+        while self.dy <= self.radius {
+            let pos = self.center.add(self.dx, self.dy);
+            self.dx += 1;
+            if self.dx > self.max_dx {
+                self.dx = -self.max_dx;
+                self.dy += 1;
+            }
+            if pos == self.center || self.grid.topology.distance(self.center, pos) > self.radius {
+                continue;
+            }
+            if let Some(cell) = self.grid.cell(pos) {
+                return Some(cell);
+            }
+        }
+        None
+    }
 }
 
 impl Grid {
@@ -20,12 +84,21 @@ impl Grid {
             width,
             height,
             tiles: vec![Tile::empty(); size],
+            topology: Topology::default(),
+            occupants: Vec::new(),
         }
     }
 
     /// Create a grid from world configuration
     pub fn from_config(config: &WorldConfig, rng: &mut ChaCha8Rng) -> Self {
+        if !config.terrain.is_uniform() {
+            let mut grid = crate::terrain::generate(config, rng);
+            grid.topology = config.topology;
+            return grid;
+        }
+
         let mut grid = Self::new(config.width, config.height);
+        grid.topology = config.topology;
 
         for y in 0..config.height {
             for x in 0..config.width {
@@ -51,25 +124,39 @@ impl Grid {
         grid
     }
 
-    /// Get tile at position (with toroidal wrapping)
+    /// The tile `pos` refers to under the grid's topology, if any
+    pub fn resolve(&self, pos: Position) -> Option<Position> {
+        self.topology.resolve(pos, self.width, self.height)
+    }
+
+    /// Neighbor of `pos` in a direction, if there is one
+    pub fn step(&self, pos: Position, direction: Direction) -> Option<Position> {
+        self.topology.step(pos, direction).and_then(|p| self.resolve(p))
+    }
+
+    /// Where a move by (`dx`, `dy`) from `pos` lands, if not past a wall
+    pub fn offset(&self, pos: Position, dx: i32, dy: i32) -> Option<Position> {
+        self.resolve(self.topology.offset(pos, dx, dy))
+    }
+
+    fn index(&self, pos: Position) -> Option<usize> {
+        self.resolve(pos).map(|pos| self.pos_to_index(pos))
+    }
+
+    /// Get tile at position; past a wall this is an obstacle
     pub fn get(&self, pos: Position) -> &Tile {
-        let wrapped = pos.wrap(self.width, self.height);
-        let index = self.pos_to_index(wrapped);
-        &self.tiles[index]
+        self.index(pos).map_or(&WALL, |index| &self.tiles[index])
     }
 
-    /// Get mutable tile at position
+    /// Get mutable tile at position. Panics past a wall.
     pub fn get_mut(&mut self, pos: Position) -> &mut Tile {
-        let wrapped = pos.wrap(self.width, self.height);
-        let index = self.pos_to_index(wrapped);
+        let index = self.index(pos).expect("position is past the edge of the grid");
         &mut self.tiles[index]
     }
 
-    /// Set tile at position
+    /// Set tile at position. Panics past a wall.
     pub fn set(&mut self, pos: Position, tile: Tile) {
-        let wrapped = pos.wrap(self.width, self.height);
-        let index = self.pos_to_index(wrapped);
-        self.tiles[index] = tile;
+        *self.get_mut(pos) = tile;
     }
 
     /// Regenerate resources on all resource tiles
@@ -79,22 +166,89 @@ impl Grid {
         }
     }
 
-    /// Get neighbors of a position (returns cloned tiles to avoid lifetime issues)
-    pub fn neighbors(&self, pos: Position, radius: i32) -> Vec<(Position, Tile)> {
-        let mut neighbors = Vec::new();
+    /// Advance plant and meat stocks on every tile by one tick
+    pub fn update_resources(&mut self, plant: &ResourceKindConfig, meat: &ResourceKindConfig) {
+        for tile in &mut self.tiles {
+            tile.update_resources(plant, meat);
+        }
+    }
 
-        for dy in -radius..=radius {
-            for dx in -radius..=radius {
-                if dx == 0 && dy == 0 {
-                    continue;
-                }
+    /// Tile, resolved position and occupant at a position, unless it is
+    /// past a wall
+    pub fn cell(&self, pos: Position) -> Option<Cell<'_>> {
+        let pos = self.resolve(pos)?;
+        let index = self.pos_to_index(pos);
+        Some(Cell {
+            pos,
+            tile: &self.tiles[index],
+            occupant: self.occupants.get(index).copied().flatten(),
+        })
+    }
 
-                let neighbor_pos = pos.add(dx, dy);
-                neighbors.push((neighbor_pos, self.get(neighbor_pos).clone()));
-            }
+    /// Cells within `radius` steps of `pos`, without `pos` itself
+    pub fn neighborhood(&self, pos: Position, radius: i32) -> Neighborhood<'_> {
+        let radius = radius.max(0);
+        let max_dx = if self.topology == Topology::Hex { radius + 1 } else { radius };
+        Neighborhood {
+            grid: self,
+            center: pos,
+            radius,
+            max_dx,
+            dx: -max_dx,
+            dy: -radius,
         }
+    }
+
+    /// Organisms within `radius` of `pos`, with their positions
+    pub fn occupants_within(
+        &self,
+        pos: Position,
+        radius: i32,
+    ) -> impl Iterator<Item = (Position, OrganismId)> + '_ {
+        self.neighborhood(pos, radius)
+            .filter_map(|cell| cell.occupant.map(|id| (cell.pos, id)))
+    }
+
+    /// Whether an organism could stand on `pos`: on the grid, passable and
+    /// empty
+    pub fn is_free(&self, pos: Position) -> bool {
+        self.cell(pos).is_some_and(|cell| cell.is_free())
+    }
 
-        neighbors
+    /// First free tile next to `pos`
+    pub fn free_neighbor(&self, pos: Position) -> Option<Position> {
+        self.neighborhood(pos, 1)
+            .find(|cell| cell.is_free())
+            .map(|cell| cell.pos)
+    }
+
+    /// Organism on a tile, if any
+    pub fn occupant(&self, pos: Position) -> Option<OrganismId> {
+        self.cell(pos).and_then(|cell| cell.occupant)
+    }
+
+    pub fn is_occupied(&self, pos: Position) -> bool {
+        self.occupant(pos).is_some()
+    }
+
+    /// Put an organism on a tile, returning whoever was there
+    pub fn place(&mut self, pos: Position, id: OrganismId) -> Option<OrganismId> {
+        if self.occupants.len() != self.tiles.len() {
+            self.occupants = vec![None; self.tiles.len()];
+        }
+        let index = self.index(pos).expect("position is past the edge of the grid");
+        self.occupants[index].replace(id)
+    }
+
+    /// Remove whoever is on a tile
+    pub fn vacate(&mut self, pos: Position) -> Option<OrganismId> {
+        let index = self.index(pos)?;
+        self.occupants.get_mut(index).and_then(Option::take)
+    }
+
+    /// Remove every organism
+    pub fn clear_occupants(&mut self) {
+        self.occupants.clear();
     }
 
     fn pos_to_index(&self, pos: Position) -> usize {
@@ -185,10 +339,52 @@ mod tests {
     fn test_neighbors() {
         let grid = Grid::new(10, 10);
         let pos = Position::new(5, 5);
-        let neighbors = grid.neighbors(pos, 1);
 
         // Should have 8 neighbors
-        assert_eq!(neighbors.len(), 8);
+        assert_eq!(grid.neighborhood(pos, 1).count(), 8);
+        assert_eq!(grid.neighborhood(pos, 2).count(), 24);
+        assert_eq!(grid.neighborhood(pos, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_bounded_topologies() {
+        let mut grid = Grid::new(10, 10);
+        grid.topology = Topology::Box;
+        let corner = Position::new(0, 0);
+
+        assert_eq!(grid.neighborhood(corner, 1).count(), 3);
+        assert_eq!(grid.get(Position::new(-1, 0)).tile_type, TileType::Obstacle);
+        assert_eq!(grid.step(corner, Direction::West), None);
+        assert_eq!(grid.offset(corner, 0, 1), Some(Position::new(0, 1)));
+
+        grid.topology = Topology::Cylinder;
+        assert_eq!(grid.neighborhood(corner, 1).count(), 5);
+        assert_eq!(grid.step(corner, Direction::West), Some(Position::new(9, 0)));
+
+        grid.topology = Topology::Hex;
+        assert_eq!(grid.neighborhood(Position::new(5, 5), 1).count(), 6);
+        assert_eq!(grid.neighborhood(Position::new(5, 4), 2).count(), 18);
+        assert_eq!(grid.step(corner, Direction::North), None);
+    }
+
+    #[test]
+    fn test_occupancy_wraps() {
+        let mut grid = Grid::new(10, 10);
+        let id = OrganismId::new();
+        assert_eq!(grid.occupant(Position::new(0, 0)), None);
+
+        grid.place(Position::new(9, 9), id);
+        assert_eq!(grid.occupant(Position::new(-1, -1)), Some(id));
+
+        // Seen from (0, 0) across both edges
+        let near: Vec<_> = grid.occupants_within(Position::new(0, 0), 1).collect();
+        assert_eq!(near, vec![(Position::new(9, 9), id)]);
+        assert!(grid
+            .neighborhood(Position::new(0, 0), 1)
+            .all(|c| c.pos.x >= 0 && c.pos.y >= 0));
+
+        assert_eq!(grid.vacate(Position::new(9, 9)), Some(id));
+        assert!(!grid.is_occupied(Position::new(9, 9)));
     }
 
     #[test]
@@ -1,5 +1,6 @@
 //! Island simulation wrapper for distributed execution.
 
+use crate::map::embed_map;
 use crate::simulation::{Simulation, SimulationResult};
 use evo_core::{JobConfig, JobId, LineageId, Result};
 use evo_ir::Program;
@@ -14,7 +15,10 @@ pub struct IslandJob {
 }
 
 impl IslandJob {
-    pub fn new(job_id: JobId, config: JobConfig, genomes: Vec<(LineageId, Program)>) -> Result<Self> {
+    /// Create a job; a map file referenced by the config is embedded in it
+    pub fn new(job_id: JobId, mut config: JobConfig, genomes: Vec<(LineageId, Program)>) -> Result<Self> {
+        embed_map(&mut config.world_config)?;
+
         let serialized_genomes = genomes
             .into_iter()
             .map(|(id, program)| program.to_bytes().map(|bytes| (id, bytes)))
@@ -27,17 +31,21 @@ impl IslandJob {
         })
     }
 
-    /// Execute this island job
-    pub fn execute(self) -> Result<IslandResult> {
+    /// Build the simulation for this job without running it
+    pub fn simulation(&self) -> Result<Simulation> {
         // Deserialize genomes
         let genomes: Vec<(LineageId, Program)> = self
             .genomes
-            .into_iter()
-            .map(|(id, bytes)| Program::from_bytes(&bytes).map(|program| (id, program)))
+            .iter()
+            .map(|(id, bytes)| Program::from_bytes(bytes).map(|program| (*id, program)))
             .collect::<Result<Vec<_>>>()?;
 
-        // Run simulation
-        let mut simulation = Simulation::new(self.config, genomes)?;
+        Simulation::new(self.config.clone(), genomes)
+    }
+
+    /// Execute this island job
+    pub fn execute(self) -> Result<IslandResult> {
+        let mut simulation = self.simulation()?;
         let result = simulation.run()?;
 
         Ok(IslandResult {
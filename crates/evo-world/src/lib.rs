@@ -2,12 +2,26 @@
 //!
 //! This module implements the 2D grid world where organisms live, compete, and evolve.
 
+pub mod behavior;
+pub mod conflict;
+pub mod event_log;
 pub mod grid;
+pub mod map;
 pub mod organism;
+pub mod regions;
+pub mod render;
+pub mod schedule;
 pub mod simulation;
+pub mod snapshot;
+pub mod terrain;
 pub mod island;
 
+pub use event_log::{Replay, SimEvent, WorldState};
 pub use grid::Grid;
+pub use map::WorldMap;
 pub use organism::Organism;
+pub use regions::Regions;
+pub use render::{FrameRecorder, Image};
 pub use simulation::Simulation;
+pub use snapshot::SimulationSnapshot;
 pub use island::{IslandJob, IslandResult};
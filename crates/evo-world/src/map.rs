@@ -0,0 +1,174 @@
+//! Hand-authored ASCII world maps.
+//!
+//! Each line of a map file is one row of tiles:
+//!
+//! | Char      | Tile                                               |
+//! |-----------|----------------------------------------------------|
+//! | `.`       | empty                                              |
+//! | `#`       | obstacle                                           |
+//! | `x`       | hazard                                             |
+//! | `*`       | resource, full (`max_resource_per_tile`)           |
+//! | `0`-`9`   | resource, filled to digit/9 of the maximum         |
+//! | `m`       | empty tile holding `max_resource_per_tile` meat    |
+//! | `S`       | empty spawn tile (founders are placed on these)    |
+//!
+//! Lines starting with `;` are comments. Rows shorter than the widest row
+//! are padded with empty tiles.
+
+use crate::grid::Grid;
+use evo_core::{Error, MapSource, Position, Result, Tile, WorldConfig};
+use std::path::Path;
+
+/// A parsed map: the grid plus the tiles founders may spawn on
+#[derive(Debug, Clone)]
+pub struct WorldMap {
+    pub grid: Grid,
+    pub spawn_zones: Vec<Position>,
+}
+
+impl WorldMap {
+    /// Parse ASCII map text; `max_resource` is the amount of a full resource tile
+    pub fn parse(text: &str, max_resource: i32) -> Result<Self> {
+        let rows: Vec<&str> = text
+            .lines()
+            .map(|line| line.trim_end())
+            .filter(|line| !line.starts_with(';'))
+            .collect();
+
+        // Ignore blank lines at the end of the file
+        let height = rows
+            .iter()
+            .rposition(|row| !row.is_empty())
+            .map(|last| last + 1)
+            .unwrap_or(0);
+        let width = rows[..height]
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        if width == 0 || height == 0 {
+            return Err(Error::Validation("Map is empty".to_string()));
+        }
+
+        let mut grid = Grid::new(width as i32, height as i32);
+        let mut spawn_zones = Vec::new();
+
+        for (y, row) in rows[..height].iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let pos = Position::new(x as i32, y as i32);
+                let tile = match c {
+                    '.' | ' ' => Tile::empty(),
+                    '#' => Tile::obstacle(),
+                    'x' => Tile::hazard(),
+                    '*' => Tile::resource(max_resource, max_resource),
+                    '0'..='9' => {
+                        let level = c.to_digit(10).unwrap() as i32;
+                        Tile::resource(max_resource * level / 9, max_resource)
+                    }
+                    'm' => Tile {
+                        meat_amount: max_resource,
+                        ..Tile::empty()
+                    },
+                    'S' => {
+                        spawn_zones.push(pos);
+                        Tile::empty()
+                    }
+                    _ => {
+                        return Err(Error::Validation(format!(
+                            "Unknown map character '{}' at line {}, column {}",
+                            c,
+                            y + 1,
+                            x + 1
+                        )))
+                    }
+                };
+                grid.set(pos, tile);
+            }
+        }
+
+        Ok(Self { grid, spawn_zones })
+    }
+
+    /// Load and parse the map referenced by a world config, if any
+    pub fn from_config(config: &WorldConfig) -> Result<Option<Self>> {
+        let text = match &config.map {
+            None => return Ok(None),
+            Some(MapSource::Inline(text)) => text.clone(),
+            Some(MapSource::File(path)) => read_map_file(path)?,
+        };
+
+        Self::parse(&text, config.max_resource_per_tile).map(Some)
+    }
+}
+
+fn read_map_file(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    std::fs::read_to_string(path).map_err(|e| {
+        Error::NotFound(format!("Failed to read map {}: {}", path.display(), e))
+    })
+}
+
+/// Replace a file map reference with the file's contents.
+///
+/// Jobs are built where the map file lives and executed elsewhere, so the
+/// map has to travel inline.
+pub fn embed_map(config: &mut WorldConfig) -> Result<()> {
+    if let Some(MapSource::File(path)) = &config.map {
+        let text = read_map_file(path)?;
+        // Fail early rather than on the worker
+        WorldMap::parse(&text, config.max_resource_per_tile)?;
+        config.map = Some(MapSource::Inline(text));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evo_core::TileType;
+
+    const VALLEYS: &str = "\
+; two valleys separated by a hazard ridge
+S..x..*
+S..x..9
+..mx..0
+";
+
+    #[test]
+    fn test_parse_map() {
+        let map = WorldMap::parse(VALLEYS, 900).unwrap();
+
+        assert_eq!((map.grid.width, map.grid.height), (7, 3));
+        assert_eq!(map.spawn_zones, vec![Position::new(0, 0), Position::new(0, 1)]);
+        assert_eq!(map.grid.get(Position::new(3, 1)).tile_type, TileType::Hazard);
+
+        let full = map.grid.get(Position::new(6, 0));
+        assert_eq!(full.tile_type, TileType::Resource);
+        assert_eq!(full.resource_amount, 900);
+        assert_eq!(map.grid.get(Position::new(6, 2)).resource_amount, 0);
+        assert_eq!(map.grid.get(Position::new(2, 2)).meat_amount, 900);
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_tiles() {
+        let err = WorldMap::parse("..\n.?\n", 100).unwrap_err();
+        assert!(err.to_string().contains("line 2, column 2"));
+        assert!(WorldMap::parse("; nothing here\n\n", 100).is_err());
+    }
+
+    #[test]
+    fn test_embed_map_inlines_file() {
+        let path = std::env::temp_dir().join(format!("evo-map-{}.txt", std::process::id()));
+        std::fs::write(&path, VALLEYS).unwrap();
+
+        let mut config = WorldConfig {
+            map: Some(MapSource::File(path.to_string_lossy().into_owned())),
+            ..Default::default()
+        };
+        embed_map(&mut config).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.map, Some(MapSource::Inline(VALLEYS.to_string())));
+    }
+}
@@ -1,5 +1,6 @@
 //! Organism state and management.
 
+use crate::behavior::BehaviorTrace;
 use evo_core::{FitnessMetrics, LineageId, OrganismId, Position};
 use evo_ir::Program;
 use evo_runtime::{OrganismContext, OrganismInstance, RuntimeConfig};
@@ -11,14 +12,22 @@ use std::sync::Arc;
 pub struct Organism {
     pub id: OrganismId,
     pub lineage_id: LineageId,
+    /// Organisms this one was born from (empty for spawned founders)
+    pub parent_ids: Vec<OrganismId>,
+    /// Number of in-world births since the founding organism
+    pub generation: u32,
     pub position: Position,
     pub energy: i32,
+    /// Energy at the start of the current tick; by the time an organism is
+    /// removed its energy is spent, so this is what its carcass is made of
+    pub tick_start_energy: i32,
     pub age: u64,
     pub birth_tick: u64,
     pub genome: Program,
     pub instance: Option<OrganismInstance>,
     pub metrics: FitnessMetrics,
     pub visited_tiles: HashSet<Position>,
+    pub behavior: BehaviorTrace,
 }
 
 impl Organism {
@@ -44,17 +53,49 @@ impl Organism {
         Self {
             id: OrganismId::new(),
             lineage_id,
+            parent_ids: Vec::new(),
+            generation: 0,
             position,
             energy,
+            tick_start_energy: energy,
             age: 0,
             birth_tick,
             genome,
             instance: None,
             metrics: FitnessMetrics::new(),
             visited_tiles: visited,
+            behavior: BehaviorTrace::new(position),
         }
     }
 
+    /// Create an offspring of the given parents.
+    ///
+    /// The offspring joins the first parent's lineage and is one generation
+    /// deeper than its deepest parent.
+    pub fn new_offspring(
+        parents: &[&Organism],
+        position: Position,
+        energy: i32,
+        genome: Program,
+        birth_tick: u64,
+    ) -> Self {
+        let lineage_id = parents
+            .first()
+            .map(|p| p.lineage_id)
+            .unwrap_or_default();
+        let mut offspring =
+            Self::new_with_birth_tick(lineage_id, position, energy, genome, birth_tick);
+
+        offspring.parent_ids = parents.iter().map(|p| p.id).collect();
+        offspring.generation = parents
+            .iter()
+            .map(|p| p.generation + 1)
+            .max()
+            .unwrap_or(0);
+
+        offspring
+    }
+
     pub fn is_alive(&self) -> bool {
         self.energy > 0
     }
@@ -73,6 +114,13 @@ impl Organism {
         }
     }
 
+    /// Consume up to `amount` energy, returning how much was taken
+    pub fn drain_energy(&mut self, amount: i32) -> i32 {
+        let before = self.energy;
+        self.consume_energy(amount);
+        before - self.energy
+    }
+
     pub fn move_to(&mut self, new_position: Position) {
         self.position = new_position;
         self.visited_tiles.insert(new_position);
@@ -111,12 +159,20 @@ impl Organism {
 pub struct OrganismData {
     pub id: OrganismId,
     pub lineage_id: LineageId,
+    #[serde(default)]
+    pub parent_ids: Vec<OrganismId>,
+    #[serde(default)]
+    pub generation: u32,
     pub position: Position,
     pub energy: i32,
     pub age: u64,
     pub birth_tick: u64,
     pub genome: Program,
     pub metrics: FitnessMetrics,
+    /// Behavior characterization (see [`crate::behavior`]); only filled in
+    /// for survivors in a `SimulationResult`
+    #[serde(default)]
+    pub behavior: Vec<f64>,
 }
 
 impl From<&Organism> for OrganismData {
@@ -124,12 +180,15 @@ impl From<&Organism> for OrganismData {
         Self {
             id: org.id,
             lineage_id: org.lineage_id,
+            parent_ids: org.parent_ids.clone(),
+            generation: org.generation,
             position: org.position,
             energy: org.energy,
             age: org.age,
             birth_tick: org.birth_tick,
             genome: org.genome.clone(),
             metrics: org.metrics.clone(),
+            behavior: Vec::new(),
         }
     }
 }
@@ -213,6 +272,37 @@ mod tests {
         assert_eq!(organism.metrics.offspring_count, 1);
     }
 
+    #[test]
+    fn test_offspring_genealogy() {
+        let parent = Organism::new(
+            LineageId::new(),
+            Position::new(0, 0),
+            1000,
+            Program::new(),
+        );
+        let child = Organism::new_offspring(
+            &[&parent],
+            Position::new(1, 0),
+            500,
+            Program::new(),
+            10,
+        );
+        let grandchild = Organism::new_offspring(
+            &[&child],
+            Position::new(2, 0),
+            250,
+            Program::new(),
+            20,
+        );
+
+        assert_eq!(child.lineage_id, parent.lineage_id);
+        assert_eq!(child.parent_ids, vec![parent.id]);
+        assert_eq!(child.generation, 1);
+        assert_eq!(grandchild.parent_ids, vec![child.id]);
+        assert_eq!(grandchild.generation, 2);
+        assert_eq!(grandchild.birth_tick, 20);
+    }
+
     #[test]
     fn test_organism_serialization() {
         let organism = Organism::new(
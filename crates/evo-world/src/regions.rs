@@ -0,0 +1,226 @@
+//! Sub-populations within one world.
+//!
+//! With more than one region the world is split into vertical strips of
+//! equal width. Each strip starts with a barrier of obstacle columns, so
+//! organisms and their offspring stay where they are. Every
+//! `migration_interval` ticks a fraction of each region's population is
+//! moved to a free tile in another region, chosen by the migration pattern.
+//! Migrants are chosen before anyone moves, so nobody migrates twice.
+
+use crate::grid::Grid;
+use evo_core::{MigrationPattern, OrganismId, Position, RegionConfig, Tile, Topology, WorldConfig};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use std::ops::Range;
+
+/// How a world is divided into regions
+#[derive(Debug, Clone)]
+pub struct Regions {
+    config: RegionConfig,
+    width: i32,
+    height: i32,
+    region_width: i32,
+    /// Whether the west edge connects to the east edge
+    wraps: bool,
+}
+
+/// An organism moving between regions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    pub id: OrganismId,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Regions {
+    pub fn new(config: &WorldConfig) -> Self {
+        let count = config.regions.count.max(1) as i32;
+        Self {
+            config: config.regions.clone(),
+            width: config.width,
+            height: config.height,
+            region_width: (config.width / count).max(1),
+            wraps: config.topology != Topology::Box,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.config.count.max(1) as usize
+    }
+
+    /// Region a tile belongs to (barriers belong to the region east of them)
+    pub fn region_of(&self, pos: Position) -> usize {
+        ((pos.x / self.region_width) as usize).min(self.count() - 1)
+    }
+
+    /// Columns covered by a region, barrier included
+    fn extent(&self, region: usize) -> Range<i32> {
+        let start = region as i32 * self.region_width;
+        let end = if region + 1 == self.count() {
+            self.width
+        } else {
+            start + self.region_width
+        };
+        start..end
+    }
+
+    /// Barrier columns at the west edge of a region. The first region of a
+    /// world without wrapping has the world's edge instead.
+    fn barrier(&self, region: usize) -> Range<i32> {
+        let extent = self.extent(region);
+        if !self.config.enabled() || (region == 0 && !self.wraps) {
+            return extent.start..extent.start;
+        }
+        let width = self.config.barrier_width.clamp(0, self.region_width - 1);
+        extent.start..extent.start + width
+    }
+
+    /// Columns organisms can live in
+    pub fn columns(&self, region: usize) -> Range<i32> {
+        self.barrier(region).end..self.extent(region).end
+    }
+
+    /// Turn the barrier columns into obstacles
+    pub fn add_barriers(&self, grid: &mut Grid) {
+        for region in 0..self.count() {
+            for x in self.barrier(region) {
+                for y in 0..self.height {
+                    grid.set(Position::new(x, y), Tile::obstacle());
+                }
+            }
+        }
+    }
+
+    /// A random free tile in a region, if one turns up
+    pub fn free_tile(
+        &self,
+        grid: &Grid,
+        region: usize,
+        rng: &mut ChaCha8Rng,
+    ) -> Option<Position> {
+        let columns = self.columns(region);
+        if columns.is_empty() {
+            return None;
+        }
+        (0..100)
+            .map(|_| {
+                Position::new(
+                    rng.gen_range(columns.clone()),
+                    rng.gen_range(0..self.height),
+                )
+            })
+            .find(|&pos| grid.is_free(pos))
+    }
+
+    /// Region a migrant from `from` moves to
+    fn destination(&self, from: usize, rng: &mut ChaCha8Rng) -> usize {
+        let count = self.count();
+        match self.config.pattern {
+            MigrationPattern::Ring => (from + 1) % count,
+            MigrationPattern::SteppingStone => {
+                if from == 0 {
+                    1
+                } else if from + 1 == count || rng.gen_bool(0.5) {
+                    from - 1
+                } else {
+                    from + 1
+                }
+            }
+        }
+    }
+
+    /// Choose this round's migrants and where they go.
+    ///
+    /// `organisms` must come in a stable order (the simulation sorts them
+    /// by id) for runs to be reproducible.
+    pub fn plan_migration(
+        &self,
+        organisms: impl IntoIterator<Item = (OrganismId, Position)>,
+        rng: &mut ChaCha8Rng,
+    ) -> Vec<Migration> {
+        if !self.config.enabled() {
+            return Vec::new();
+        }
+
+        let mut residents = vec![Vec::new(); self.count()];
+        for (id, pos) in organisms {
+            residents[self.region_of(pos)].push(id);
+        }
+
+        let rate = self.config.migration_rate.clamp(0.0, 1.0);
+        let mut migrations = Vec::new();
+        for (from, ids) in residents.iter().enumerate() {
+            let amount = (ids.len() as f32 * rate).round() as usize;
+            for &id in ids.choose_multiple(rng, amount) {
+                let to = self.destination(from, rng);
+                migrations.push(Migration { id, from, to });
+            }
+        }
+        migrations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn world(count: u32, pattern: MigrationPattern, topology: Topology) -> WorldConfig {
+        WorldConfig {
+            width: 30,
+            height: 10,
+            topology,
+            regions: RegionConfig {
+                count,
+                barrier_width: 2,
+                migration_rate: 0.5,
+                pattern,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_barriers_separate_regions() {
+        let regions = Regions::new(&world(3, MigrationPattern::Ring, Topology::Torus));
+        let mut grid = Grid::new(30, 10);
+        regions.add_barriers(&mut grid);
+
+        assert_eq!(regions.columns(0), 2..10);
+        assert_eq!(regions.columns(2), 22..30);
+        for x in [0, 1, 10, 11, 20, 21] {
+            assert!(!grid.is_free(Position::new(x, 4)), "column {x}");
+        }
+        assert!(grid.is_free(Position::new(2, 4)));
+        assert_eq!(regions.region_of(Position::new(25, 0)), 2);
+
+        // Without wrapping the world edge separates the first region
+        let regions = Regions::new(&world(3, MigrationPattern::Ring, Topology::Box));
+        assert_eq!(regions.columns(0), 0..10);
+        assert_eq!(regions.columns(1), 12..20);
+    }
+
+    #[test]
+    fn test_migration_patterns() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let organisms: Vec<_> = (0..60)
+            .map(|i| (OrganismId::new(), Position::new(i % 30, 0)))
+            .collect();
+
+        let ring = Regions::new(&world(3, MigrationPattern::Ring, Topology::Torus));
+        let migrations = ring.plan_migration(organisms.clone(), &mut rng);
+        assert_eq!(migrations.len(), 30);
+        assert!(migrations.iter().all(|m| m.to == (m.from + 1) % 3));
+
+        let stones = Regions::new(&world(3, MigrationPattern::SteppingStone, Topology::Torus));
+        let migrations = stones.plan_migration(organisms.clone(), &mut rng);
+        assert!(migrations.iter().all(|m| m.from.abs_diff(m.to) == 1));
+        assert!(migrations.iter().any(|m| m.from == 1 && m.to == 0));
+        assert!(migrations.iter().any(|m| m.from == 1 && m.to == 2));
+
+        let single = Regions::new(&world(1, MigrationPattern::Ring, Topology::Torus));
+        assert!(single.plan_migration(organisms, &mut rng).is_empty());
+    }
+}
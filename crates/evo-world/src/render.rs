@@ -0,0 +1,244 @@
+//! Rendering world state to images.
+//!
+//! Images are written as binary PPM (P6), which needs no image library and
+//! is understood by most viewers and by ffmpeg for building timelapses.
+
+use crate::grid::Grid;
+use evo_core::{LineageId, Position, Result, Tile, TileType};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub type Rgb = [u8; 3];
+
+const EMPTY_COLOR: Rgb = [24, 24, 28];
+const OBSTACLE_COLOR: Rgb = [110, 110, 118];
+const HAZARD_COLOR: Rgb = [120, 28, 28];
+const RESOURCE_COLOR: Rgb = [40, 170, 60];
+const MEAT_COLOR: Rgb = [190, 90, 60];
+const RADIATION_COLOR: Rgb = [210, 220, 40];
+
+/// Meat at or above this amount is drawn in full color
+const MEAT_FULL_AMOUNT: f32 = 500.0;
+
+/// An RGB image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, fill: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; (width * height) as usize],
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Rgb {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, color: Rgb) {
+        self.pixels[(y * self.width + x) as usize] = color;
+    }
+
+    fn fill_rect(&mut self, x: u32, y: u32, size: u32, color: Rgb) {
+        for py in y..y + size {
+            for px in x..x + size {
+                self.set(px, py, color);
+            }
+        }
+    }
+
+    /// Write the image as binary PPM
+    pub fn write_ppm(&self, mut writer: impl Write) -> Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for pixel in &self.pixels {
+            writer.write_all(pixel)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn save_ppm(&self, path: impl AsRef<Path>) -> Result<()> {
+        let file = File::create(path)?;
+        self.write_ppm(BufWriter::new(file))
+    }
+}
+
+/// Stable, well-separated color for a lineage
+pub fn lineage_color(lineage_id: LineageId) -> Rgb {
+    let bytes = lineage_id.0.as_bytes();
+    let hue = u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / u16::MAX as f32;
+    hsv_to_rgb(hue * 360.0, 0.75, 1.0)
+}
+
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> Rgb {
+    let c = v * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = v - c;
+
+    let (r, g, b) = match h as u32 {
+        0..=59 => (c, x, 0.0),
+        60..=119 => (x, c, 0.0),
+        120..=179 => (0.0, c, x),
+        180..=239 => (0.0, x, c),
+        240..=299 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+
+    [
+        ((r + m) * 255.0) as u8,
+        ((g + m) * 255.0) as u8,
+        ((b + m) * 255.0) as u8,
+    ]
+}
+
+/// Color of a tile; resource tiles fade towards empty as they are depleted
+/// and meat is drawn over the tile, fading as it rots. Radiation tints
+/// the tile yellow.
+pub fn tile_color(tile: &Tile) -> Rgb {
+    let mut color = base_tile_color(tile);
+    if tile.meat_amount > 0 {
+        let fill = (tile.meat_amount as f32 / MEAT_FULL_AMOUNT).clamp(0.25, 1.0);
+        color = blend(color, MEAT_COLOR, fill);
+    }
+    if tile.radiation > 0.0 {
+        color = blend(color, RADIATION_COLOR, 0.3);
+    }
+    color
+}
+
+fn base_tile_color(tile: &Tile) -> Rgb {
+    match tile.tile_type {
+        TileType::Empty => EMPTY_COLOR,
+        TileType::Obstacle => OBSTACLE_COLOR,
+        TileType::Hazard => HAZARD_COLOR,
+        TileType::Resource => {
+            let fill = if tile.max_resource > 0 {
+                (tile.resource_amount as f32 / tile.max_resource as f32).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            blend(EMPTY_COLOR, RESOURCE_COLOR, fill)
+        }
+    }
+}
+
+fn blend(from: Rgb, to: Rgb, t: f32) -> Rgb {
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    [mix(from[0], to[0]), mix(from[1], to[1]), mix(from[2], to[2])]
+}
+
+/// Render a grid with organisms drawn on top.
+///
+/// Each tile becomes a `scale`×`scale` block; organisms are drawn as a block
+/// in their lineage color with a one pixel border of the tile underneath
+/// when the scale allows it.
+pub fn render(
+    grid: &Grid,
+    organisms: impl IntoIterator<Item = (Position, LineageId)>,
+    scale: u32,
+) -> Image {
+    let scale = scale.max(1);
+    let mut image = Image::new(
+        grid.width as u32 * scale,
+        grid.height as u32 * scale,
+        EMPTY_COLOR,
+    );
+
+    for (pos, tile) in grid.iter() {
+        image.fill_rect(pos.x as u32 * scale, pos.y as u32 * scale, scale, tile_color(tile));
+    }
+
+    let inset = if scale >= 3 { 1 } else { 0 };
+    for (pos, lineage_id) in organisms {
+        let pos = pos.wrap(grid.width, grid.height);
+        image.fill_rect(
+            pos.x as u32 * scale + inset,
+            pos.y as u32 * scale + inset,
+            scale - 2 * inset,
+            lineage_color(lineage_id),
+        );
+    }
+
+    image
+}
+
+/// Writes numbered frames into a directory every `every` ticks
+pub struct FrameRecorder {
+    dir: PathBuf,
+    every: u64,
+    scale: u32,
+}
+
+impl FrameRecorder {
+    /// Create the output directory if needed
+    pub fn new(dir: impl Into<PathBuf>, every: u64, scale: u32) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            every: every.max(1),
+            scale,
+        })
+    }
+
+    pub fn wants(&self, tick: u64) -> bool {
+        tick.is_multiple_of(self.every)
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Path of the frame for a tick (zero padded so files sort by tick)
+    pub fn frame_path(&self, tick: u64) -> PathBuf {
+        self.dir.join(format!("frame_{:08}.ppm", tick))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_tiles_and_organisms() {
+        let mut grid = Grid::new(4, 3);
+        grid.set(Position::new(1, 0), Tile::obstacle());
+        grid.set(Position::new(2, 0), Tile::resource(100, 100));
+        grid.set(Position::new(3, 0), Tile::resource(0, 100));
+
+        let lineage = LineageId::new();
+        let image = render(&grid, vec![(Position::new(0, 2), lineage)], 4);
+
+        assert_eq!((image.width, image.height), (16, 12));
+        assert_eq!(image.get(4, 0), OBSTACLE_COLOR);
+        assert_eq!(image.get(8, 0), RESOURCE_COLOR);
+        assert_eq!(image.get(12, 0), EMPTY_COLOR);
+
+        let carcass = Tile {
+            meat_amount: 1000,
+            ..Tile::empty()
+        };
+        assert_eq!(tile_color(&carcass), MEAT_COLOR);
+
+        // Organism block with a border of the tile underneath
+        assert_eq!(image.get(1, 9), lineage_color(lineage));
+        assert_eq!(image.get(0, 8), EMPTY_COLOR);
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let image = Image::new(2, 1, [1, 2, 3]);
+        let mut bytes = Vec::new();
+        image.write_ppm(&mut bytes).unwrap();
+
+        assert!(bytes.starts_with(b"P6\n2 1\n255\n"));
+        assert_eq!(&bytes[bytes.len() - 6..], &[1, 2, 3, 1, 2, 3]);
+    }
+}
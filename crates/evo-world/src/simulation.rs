@@ -1,43 +1,85 @@
 //! Simulation engine for running an island.
 
+use crate::conflict::{self, AttackIntent, Claimant, MealIntent, MoveIntent};
+use crate::event_log::{EventLogHeader, EventLogWriter, ReplayOrganism, SimEvent};
 use crate::grid::Grid;
+use crate::map::WorldMap;
 use crate::organism::{Organism, OrganismData};
+use crate::regions::Regions;
+use crate::render::{self, FrameRecorder, Image};
+use crate::schedule;
+use crate::snapshot::{OrganismSnapshot, RngState, SimulationSnapshot, SNAPSHOT_VERSION};
 use evo_core::{
-    EnergyConfig, Error, FitnessMetrics, JobConfig, LineageId, OrganismId, Position, Result,
-    TileType,
+    Direction, EnergyConfig, Error, FitnessMetrics, JobConfig, LineageId, OrganismId, Position,
+    ResourceKind, Result, Tile, TileType,
 };
 use evo_ir::{Compiler, Mutator, MutationConfig, Program};
-use evo_runtime::{HostFunctions, OrganismContext, Runtime, RuntimeConfig};
+use evo_runtime::context::Action;
+use evo_runtime::{
+    HostFunctions, OrganismContext, OrganismInstance, Runtime, RuntimeConfig, NEIGHBOR_KIN,
+    NEIGHBOR_NONE, NEIGHBOR_OTHER,
+};
 use parking_lot::RwLock;
 use rand::seq::SliceRandom;
+use rayon::prelude::*;
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::sync::Arc;
 use tracing::{debug, info, warn, instrument, trace, event, Level};
 
 pub struct Simulation {
     grid: Arc<RwLock<Grid>>,
     organisms: HashMap<OrganismId, Organism>,
-    organism_positions: HashMap<Position, OrganismId>,
     runtime: Runtime,
     compiler: Compiler,
     mutator: Mutator,
     config: JobConfig,
     rng: ChaCha8Rng,
     tick: u64,
+    /// Next tick `run` will execute
+    next_tick: u64,
     // Reproduction tracking for metrics
     reproduction_attempts: u64,
     reproduction_successes: u64,
     total_offspring_born: u64,
+    births: Vec<BirthRecord>,
+    event_log: Option<EventLogWriter>,
+    frames: Option<FrameRecorder>,
+    /// Tiles founders spawn on (anywhere if empty)
+    spawn_zones: Vec<Position>,
+    /// Mating proposals (proposer, partner) made this tick, in order
+    mate_proposals: Vec<(OrganismId, OrganismId)>,
+    /// Energy flows of the current (or last) tick
+    energy_audit: EnergyAudit,
+    regions: Regions,
+    /// Immigrants and emigrants so far, per region
+    migration_counts: Vec<(u64, u64)>,
 }
 
 impl Simulation {
-    pub fn new(config: JobConfig, genomes: Vec<(LineageId, Program)>) -> Result<Self> {
+    pub fn new(mut config: JobConfig, genomes: Vec<(LineageId, Program)>) -> Result<Self> {
+        config.world_config.validate_sense_codes()?;
+        config.world_config.validate_meat_curve()?;
         let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
-        let grid = Arc::new(RwLock::new(Grid::from_config(&config.world_config, &mut rng)));
+
+        // A hand-authored map replaces generated terrain and sets the size
+        let (mut grid, spawn_zones) = match WorldMap::from_config(&config.world_config)? {
+            Some(map) => {
+                config.world_config.width = map.grid.width;
+                config.world_config.height = map.grid.height;
+                (map.grid, map.spawn_zones)
+            }
+            None => (Grid::from_config(&config.world_config, &mut rng), Vec::new()),
+        };
+        grid.topology = config.world_config.topology;
+        let regions = Regions::new(&config.world_config);
+        regions.add_barriers(&mut grid);
+        crate::terrain::add_radiation(&mut grid, &config.world_config.radiation, &mut rng);
+        let grid = Arc::new(RwLock::new(grid));
 
         let runtime_config = RuntimeConfig {
             max_fuel: config.exec_config.max_fuel_per_step,
@@ -51,45 +93,181 @@ impl Simulation {
         let mut sim = Self {
             grid,
             organisms: HashMap::new(),
-            organism_positions: HashMap::new(),
             runtime,
             compiler,
             mutator,
             config,
             rng,
             tick: 0,
+            next_tick: 0,
             reproduction_attempts: 0,
             reproduction_successes: 0,
             total_offspring_born: 0,
+            births: Vec::new(),
+            event_log: None,
+            frames: None,
+            spawn_zones,
+            mate_proposals: Vec::new(),
+            energy_audit: EnergyAudit::default(),
+            migration_counts: vec![(0, 0); regions.count()],
+            regions,
         };
 
-        // Spawn initial organisms
-        for (lineage_id, genome) in genomes {
-            sim.spawn_organism(lineage_id, genome)?;
+        // Spawn initial organisms, spread over the regions
+        for (index, (lineage_id, genome)) in genomes.into_iter().enumerate() {
+            let region = index % sim.regions.count();
+            sim.spawn_organism(lineage_id, genome, region)?;
         }
 
         Ok(sim)
     }
 
-    /// Run the simulation for the specified number of ticks
-    #[instrument(skip(self), fields(num_ticks = self.config.num_ticks))]
-    pub fn run(&mut self) -> Result<SimulationResult> {
-        info!("Starting simulation for {} ticks", self.config.num_ticks);
+    /// Record a structured event log of the rest of the run to `writer`.
+    ///
+    /// The log header captures the current world, so recording can start
+    /// at any point; see [`crate::event_log`] for the format.
+    pub fn record_events(&mut self, writer: impl Write + Send + 'static) -> Result<()> {
+        let header = EventLogHeader {
+            config: self.config.clone(),
+            start_tick: self.next_tick,
+            grid: self.grid.read().clone(),
+            organisms: self.organisms.values().map(ReplayOrganism::from).collect(),
+        };
+        self.event_log = Some(EventLogWriter::new(writer, &header)?);
+        Ok(())
+    }
+
+    /// Write a rendered frame every few ticks during the run
+    pub fn record_frames(&mut self, recorder: FrameRecorder) {
+        self.frames = Some(recorder);
+    }
+
+    /// Render the current world with organisms colored by lineage
+    pub fn render(&self, scale: u32) -> Image {
+        let grid = self.grid.read();
+        render::render(
+            &grid,
+            self.organisms.values().map(|o| (o.position, o.lineage_id)),
+            scale,
+        )
+    }
+
+    fn log_event(&mut self, event: SimEvent) {
+        if let Some(log) = self.event_log.as_mut() {
+            log.record(event);
+        }
+    }
+
+    /// Capture the complete simulation state between ticks
+    pub fn snapshot(&mut self) -> SimulationSnapshot {
+        let mut organisms: Vec<OrganismSnapshot> = self
+            .organisms
+            .values_mut()
+            .map(OrganismSnapshot::capture)
+            .collect();
+        organisms.sort_unstable_by_key(|o| o.id.0);
+
+        SimulationSnapshot {
+            version: SNAPSHOT_VERSION,
+            config: self.config.clone(),
+            tick: self.tick,
+            next_tick: self.next_tick,
+            grid: self.grid.read().clone(),
+            organisms,
+            rng: RngState::from(&self.rng),
+            births: self.births.clone(),
+            reproduction_attempts: self.reproduction_attempts,
+            reproduction_successes: self.reproduction_successes,
+            total_offspring_born: self.total_offspring_born,
+            migration_counts: self.migration_counts.clone(),
+        }
+    }
 
-        for tick in 0..self.config.num_ticks {
-            self.tick = tick;
+    /// Rebuild a simulation from a snapshot.
+    ///
+    /// Genomes are recompiled and instantiated organisms get their WASM
+    /// memory and globals back, so the run continues exactly as if it had
+    /// never stopped.
+    pub fn restore(snapshot: SimulationSnapshot) -> Result<Self> {
+        let mut sim = Self::new(snapshot.config, Vec::new())?;
+
+        sim.tick = snapshot.tick;
+        sim.next_tick = snapshot.next_tick;
+        sim.grid = Arc::new(RwLock::new(snapshot.grid));
+        sim.rng = snapshot.rng.to_rng();
+        sim.births = snapshot.births;
+        sim.reproduction_attempts = snapshot.reproduction_attempts;
+        sim.reproduction_successes = snapshot.reproduction_successes;
+        sim.total_offspring_born = snapshot.total_offspring_born;
+        sim.migration_counts = snapshot.migration_counts;
+
+        for saved in &snapshot.organisms {
+            let mut organism = saved.to_organism();
+
+            if let Some(state) = &saved.instance {
+                let mut instance = sim.build_instance(&organism)?;
+                instance.restore(state)?;
+                organism.instance = Some(instance);
+            }
+
+            sim.grid.write().place(organism.position, organism.id);
+            sim.organisms.insert(organism.id, organism);
+        }
+
+        Ok(sim)
+    }
+
+    pub fn config(&self) -> &JobConfig {
+        &self.config
+    }
+
+    /// Last tick that was run
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Energy flows of the last tick that was run
+    pub fn energy_audit(&self) -> &EnergyAudit {
+        &self.energy_audit
+    }
+
+    /// Run ticks up to (but not including) `end_tick`, capped at the job's
+    /// `num_ticks`. Use this to split a job into shorter leases.
+    pub fn run_until(&mut self, end_tick: u64) -> Result<()> {
+        let end_tick = end_tick.min(self.config.num_ticks);
+
+        while self.next_tick < end_tick {
+            self.tick = self.next_tick;
             self.step()?;
+            self.next_tick += 1;
 
-            if tick % 1000 == 0 {
+            if self.tick.is_multiple_of(1000) {
                 info!(
                     "Tick {}/{}: {} organisms alive",
-                    tick,
+                    self.tick,
                     self.config.num_ticks,
                     self.organisms.len()
                 );
             }
         }
 
+        if let Some(log) = self.event_log.as_mut() {
+            log.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Run the simulation for the specified number of ticks
+    #[instrument(skip(self), fields(num_ticks = self.config.num_ticks))]
+    pub fn run(&mut self) -> Result<SimulationResult> {
+        info!(
+            "Starting simulation at tick {} of {}",
+            self.next_tick, self.config.num_ticks
+        );
+
+        self.run_until(self.config.num_ticks)?;
+
         // Emit comprehensive episode summary
         self.emit_episode_summary();
 
@@ -213,22 +391,67 @@ impl Simulation {
     /// Execute one simulation step
     // #[instrument(skip(self), fields(tick = self.tick))]
     fn step(&mut self) -> Result<()> {
-        // Regenerate resources
-        self.grid
-            .write()
-            .regenerate_resources(self.config.world_config.resource_regen_rate);
+        if let Some(log) = self.event_log.as_mut() {
+            log.begin_tick(self.tick);
+        }
+
+        let mut start = 0;
+        for organism in self.organisms.values_mut() {
+            organism.tick_start_energy = organism.energy;
+            start += organism.energy as i64;
+        }
+        let mut food = self.food_by_tile();
+        self.energy_audit = EnergyAudit {
+            tick: self.tick,
+            start,
+            food_start: food.iter().sum(),
+            ..Default::default()
+        };
+
+        if !self.config.schedule.is_empty() {
+            // Occupied tiles are kept clear by the grid itself
+            let changed =
+                schedule::apply(&mut self.config, &mut self.grid.write(), self.tick, |_| false);
+            if changed {
+                let (added, removed, after) = self.food_changes(&food);
+                self.energy_audit.relayout_added = added;
+                self.energy_audit.relayout_removed = removed;
+                food = after;
+            }
+        }
+
+        // Regrow plants and rot meat
+        let plant = self.config.resource_kind(ResourceKind::Plant);
+        let meat = self.config.resource_kind(ResourceKind::Meat);
+        self.grid.write().update_resources(&plant, &meat);
+        let (regrown, rotted, _) = self.food_changes(&food);
+        self.energy_audit.regrown = regrown;
+        self.energy_audit.rotted = rotted;
 
-        // Get list of organism IDs to process (to avoid borrow issues)
-        let organism_ids: Vec<OrganismId> = self.organisms.keys().copied().collect();
+        // Get list of organism IDs to process (to avoid borrow issues),
+        // sorted so the shuffle only depends on the RNG
+        let mut shuffled_ids: Vec<OrganismId> = self.organisms.keys().copied().collect();
+        shuffled_ids.sort_unstable_by_key(|id| id.0);
 
         // Shuffle for fairness
-        let mut shuffled_ids = organism_ids.clone();
         shuffled_ids.shuffle(&mut self.rng);
 
-        // Process each organism
-        for id in shuffled_ids {
-            self.process_organism(id)?;
+        // Upkeep in shuffled order, then every organism decides what to do
+        // in parallel, then the actions are applied in the same order
+        let turns: Vec<Turn> = shuffled_ids
+            .into_iter()
+            .filter_map(|id| self.begin_turn(id))
+            .collect();
+        let mut outcomes = self.run_turns(&turns);
+        let mut intents = Intents::default();
+        for (index, turn) in turns.iter().enumerate() {
+            if let Some(outcome) = outcomes.remove(&turn.id) {
+                self.finish_turn(turn.id, index, outcome, &mut intents);
+            }
         }
+        self.resolve_intents(intents)?;
+
+        self.resolve_matings();
 
         // Apply hazard damage
         self.apply_hazards();
@@ -236,11 +459,26 @@ impl Simulation {
         // Remove dead organisms
         self.remove_dead_organisms();
 
+        if self.config.world_config.regions.migrates_at(self.tick) {
+            self.migrate();
+        }
+
+        self.close_energy_audit();
+
         // Periodic metrics (every 100 ticks)
         if self.tick % 100 == 0 && self.tick > 0 {
             self.emit_population_metrics();
         }
 
+        if let Some(log) = self.event_log.as_mut() {
+            log.end_tick()?;
+        }
+
+        if let Some(frames) = self.frames.as_ref().filter(|f| f.wants(self.tick)) {
+            self.render(frames.scale())
+                .save_ppm(frames.frame_path(self.tick))?;
+        }
+
         Ok(())
     }
 
@@ -337,173 +575,262 @@ impl Simulation {
         );
     }
 
-    fn process_organism(&mut self, id: OrganismId) -> Result<()> {
-        // Check if organism still exists (might have been killed)
-        if !self.organisms.contains_key(&id) {
-            return Ok(());
-        }
-
-        // Get organism (we'll need to work around borrow checker)
-        let organism = self.organisms.get_mut(&id).unwrap();
+    /// Pay an organism's upkeep and age it before it runs.
+    ///
+    /// Returns the turn it should take this tick, or None if it died.
+    fn begin_turn(&mut self, id: OrganismId) -> Option<Turn> {
+        let organism = self.organisms.get_mut(&id)?;
 
         // Apply basal metabolic cost
-        if !organism.consume_energy(self.config.energy_config.basal_cost) {
+        let energy_before = organism.energy;
+        let energy_config = &self.config.energy_config;
+        let basal_cost = energy_config.aging.basal_cost(energy_config.basal_cost, organism.age);
+        let paid = organism.consume_energy(basal_cost);
+        self.energy_audit.metabolism += (energy_before - organism.energy) as i64;
+        if !paid {
             organism.finalize_metrics(self.config.energy_config.initial_energy);
-            return Ok(()); // Will be removed in cleanup phase
+            return None; // Will be removed in cleanup phase
         }
 
         organism.tick();
+        {
+            let grid = self.grid.read();
+            let tile_type = grid.get(organism.position).tile_type;
+            organism
+                .behavior
+                .record_tick(organism.position, tile_type, grid.width, grid.height);
+        }
 
-        // Compile and execute organism if not already done
-        if organism.instance.is_none() {
-            let wasm_bytes = self.compiler.compile(&organism.genome)?;
-            let position = organism.position;
-            let energy = organism.energy;
+        if self.config.energy_config.aging.outlived(organism.age) {
+            debug!(organism_id = ?id, age = organism.age, tick = self.tick, "Organism died of old age");
+            organism.finalize_metrics(self.config.energy_config.initial_energy);
+            self.energy_audit.removed += organism.drain_energy(organism.energy) as i64;
+            return None;
+        }
 
-            // Create context with environment query
-            let grid_clone = self.grid.clone();
-            let env_query = Arc::new(move |x: i32, y: i32| {
-                let grid = grid_clone.read();
-                let tile = grid.get(Position::new(x, y));
-                match tile.tile_type {
-                    TileType::Empty => 0,
-                    TileType::Resource => 1,
-                    TileType::Obstacle => 2,
-                    TileType::Hazard => 3,
-                }
-            });
+        // New organisms are instantiated during the parallel phase, with an
+        // init seed drawn here so it doesn't depend on thread scheduling
+        let init_seed = organism.instance.is_none().then(|| self.rng.gen());
 
-            let context = Arc::new(OrganismContext::new(id, energy, position, env_query));
-            let host_functions = HostFunctions::new(context.clone());
+        Some(Turn {
+            id,
+            init_seed,
+            neighbors: self.neighbor_codes(id),
+        })
+    }
 
-            let mut instance = self.runtime.instantiate(&wasm_bytes, host_functions)?;
-            instance.init(self.rng.gen())?;
+    /// Run the WASM step of every organism taking a turn, in parallel.
+    ///
+    /// The grid and other organisms are not modified while this runs, so
+    /// each organism sees the world as it was at the start of the phase and
+    /// the outcomes don't depend on the number of threads.
+    fn run_turns(&mut self, turns: &[Turn]) -> HashMap<OrganismId, StepOutcome> {
+        let factory = InstanceFactory::new(&self.compiler, &self.runtime, &self.grid, &self.config);
+        let turns: HashMap<OrganismId, &Turn> = turns.iter().map(|t| (t.id, t)).collect();
+
+        self.organisms
+            .par_iter_mut()
+            .filter_map(|(id, organism)| turns.get(id).map(|turn| (*id, organism, *turn)))
+            .map(|(id, organism, turn)| (id, run_turn(&factory, organism, turn)))
+            .collect()
+    }
 
-            organism.instance = Some(instance);
-        }
+    /// Pay for an organism's step and collect what it wants to do
+    fn finish_turn(&mut self, id: OrganismId, turn: usize, outcome: StepOutcome, intents: &mut Intents) {
+        let Some(organism) = self.organisms.get_mut(&id).filter(|o| o.is_alive()) else {
+            return;
+        };
 
-        // Execute step
-        let instance = organism.instance.as_mut().unwrap();
-        let (_, actions) = match instance.step(0) {
-            Ok(result) => result,
-            Err(e) => {
+        let (actions, fuel_used) = match outcome {
+            StepOutcome::Acted { actions, fuel_used } => (actions, fuel_used),
+            StepOutcome::NotViable(e) => {
+                // Genomes that don't compile to valid WASM are not viable
+                debug!("Organism {:?} is not viable: {}", id, e);
+                self.energy_audit.removed += organism.drain_energy(organism.energy) as i64;
+                return;
+            }
+            StepOutcome::Failed(e) => {
                 info!("Organism {:?} execution failed: {}", id, e);
-                return Ok(());
+                return;
             }
         };
 
         // Apply instruction cost
-        let fuel_used = instance.fuel_consumed();
         let instruction_cost =
             (fuel_used / 1000) as i32 * self.config.energy_config.instruction_cost_per_k;
-        organism.consume_energy(instruction_cost);
-
-        // Process actions (collect them first to avoid borrow issues)
-        let organism_pos = organism.position;
-        let organism_energy = organism.energy;
+        self.energy_audit.metabolism += organism.drain_energy(instruction_cost) as i64;
 
+        // Sort the actions into intents to resolve together
+        let claimant = Claimant {
+            id,
+            energy: organism.energy,
+            turn,
+        };
+        let rules = &self.config.dynamic_rules;
+        let mut moved = false;
         for action in actions {
-            self.apply_action(id, action, organism_pos, organism_energy)?;
+            organism.behavior.record_action(&action);
+            match action {
+                Action::Move { dx, dy } => {
+                    if !moved && claimant.energy >= self.config.energy_config.move_cost {
+                        if let Some(to) = self.grid.read().offset(organism.position, dx, dy) {
+                            intents.moves.push(MoveIntent { claimant, to });
+                        }
+                    }
+                    moved = true;
+                }
+                Action::Eat { kind } => intents.meals.push(MealIntent { claimant, kind }),
+                Action::Attack {
+                    target_slot,
+                    amount,
+                } if rules.allow_combat => intents.attacks.push((claimant, target_slot, amount)),
+                Action::Attack { .. } => {}
+                other => intents.other.push((id, other)),
+            }
         }
+    }
 
-        Ok(())
+    /// Compile an organism's genome and instantiate it (without calling init)
+    fn build_instance(&self, organism: &Organism) -> Result<OrganismInstance> {
+        InstanceFactory::new(&self.compiler, &self.runtime, &self.grid, &self.config).build(organism)
     }
 
-    fn apply_action(
-        &mut self,
-        id: OrganismId,
-        action: evo_runtime::context::Action,
-        pos: Position,
-        energy: i32,
-    ) -> Result<()> {
-        use evo_runtime::context::Action;
+    /// Resolve the tick's intents against each other and apply them; see
+    /// [`crate::conflict`] for the rules
+    fn resolve_intents(&mut self, intents: Intents) -> Result<()> {
+        let alive = |organisms: &HashMap<OrganismId, Organism>, id| {
+            organisms.get(&id).is_some_and(|o| o.is_alive())
+        };
 
-        match action {
-            Action::Move { dx, dy } => {
-                if energy < self.config.energy_config.move_cost {
-                    return Ok(());
-                }
+        // Attacks land together; each attacker pays for each attack it makes
+        let attack_cost = self.config.energy_config.attack_cost;
+        let mut attacks = Vec::new();
+        for (attacker, slot, damage) in intents.attacks {
+            let Some(target) = self.neighbor_in_slot(attacker.id, slot) else {
+                continue;
+            };
+            let organism = self.organisms.get_mut(&attacker.id).unwrap();
+            if organism.energy < attack_cost {
+                continue;
+            }
+            self.energy_audit.actions += organism.drain_energy(attack_cost) as i64;
+            organism.record_damage_dealt(damage.max(0));
+            attacks.push(AttackIntent {
+                attacker,
+                target,
+                damage,
+            });
+        }
+        for hit in conflict::resolve_attacks(&attacks) {
+            let target = self.organisms.get_mut(&hit.target).unwrap();
+            target.record_damage_received(hit.damage);
+            self.energy_audit.combat += target.drain_energy(hit.damage) as i64;
+            if !target.is_alive() {
+                self.organisms.get_mut(&hit.killer).unwrap().record_kill();
+            }
+        }
+        for attack in &attacks {
+            let attacker_energy = self.organisms[&attack.attacker.id].energy;
+            let target_energy = self.organisms[&attack.target].energy;
+            self.log_event(SimEvent::Attack {
+                attacker_id: attack.attacker.id,
+                target_id: attack.target,
+                damage: attack.damage.max(0),
+                attacker_energy,
+                target_energy,
+            });
+        }
 
-                let (width, height) = {
-                    let grid = self.grid.read();
-                    (grid.width, grid.height)
-                };
-                let new_pos = pos.add(dx, dy).wrap(width, height);
-
-                // Check if target is occupied
-                if !self.organism_positions.contains_key(&new_pos) {
-                    // Check if target is passable
-                    let tile_type = {
-                        let grid = self.grid.read();
-                        grid.get(new_pos).tile_type
-                    };
-
-                    if tile_type != TileType::Obstacle {
-                        // Move organism
-                        self.organism_positions.remove(&pos);
-                        self.organism_positions.insert(new_pos, id);
-
-                        if let Some(organism) = self.organisms.get_mut(&id) {
-                            organism.move_to(new_pos);
-                            organism.consume_energy(self.config.energy_config.move_cost);
-                        }
-                    }
-                }
+        // Moves into tiles that were free when the tick started
+        let moves: Vec<MoveIntent> = intents
+            .moves
+            .into_iter()
+            .filter(|m| alive(&self.organisms, m.claimant.id))
+            .collect();
+        let moves = {
+            let grid = self.grid.read();
+            conflict::resolve_moves(&moves, |pos| grid.is_free(pos))
+        };
+        for MoveIntent { claimant, to } in moves {
+            let organism = self.organisms.get_mut(&claimant.id).unwrap();
+            {
+                let mut grid = self.grid.write();
+                grid.vacate(organism.position);
+                grid.place(to, claimant.id);
             }
+            organism.move_to(to);
+            self.energy_audit.actions +=
+                organism.drain_energy(self.config.energy_config.move_cost) as i64;
 
-            Action::Eat => {
-                if let Some(organism) = self.organisms.get_mut(&id) {
-                    let organism_pos = organism.position;
-                    let mut grid = self.grid.write();
-                    let tile = grid.get_mut(organism_pos);
-                    if tile.tile_type == TileType::Resource && tile.resource_amount > 0 {
-                        let consumed = tile.resource_amount.min(100);
-                        tile.resource_amount -= consumed;
-
-                        let energy_gained =
-                            (consumed as f32 * self.config.energy_config.eat_efficiency) as i32;
-                        drop(grid); // Release lock before mutating organism
-                        organism.add_energy(energy_gained);
-                        organism.metrics.times_eaten += 1;
-                    }
-                }
+            let energy = organism.energy;
+            self.log_event(SimEvent::Move {
+                organism_id: claimant.id,
+                to,
+                energy,
+            });
+        }
+
+        // Eating from the tiles organisms ended up on
+        let mut meals = intents.meals;
+        conflict::meal_order(&mut meals);
+        for meal in meals {
+            if alive(&self.organisms, meal.claimant.id) {
+                self.eat(meal.claimant.id, meal.kind);
             }
+        }
 
-            Action::Attack { target_slot: _, amount } if self.config.dynamic_rules.allow_combat => {
-                if energy < self.config.energy_config.attack_cost {
-                    return Ok(());
-                }
+        for (id, action) in intents.other {
+            if alive(&self.organisms, id) {
+                self.apply_action(id, action)?;
+            }
+        }
 
-                // Find target in neighboring cells
-                // This is simplified - a full implementation would use target_slot properly
-                let neighbors = {
-                    let grid = self.grid.read();
-                    grid.neighbors(pos, 1)
-                };
+        Ok(())
+    }
 
-                if let Some((target_pos, _)) = neighbors.first() {
-                    if let Some(&target_id) = self.organism_positions.get(target_pos) {
-                        // First, damage the target
-                        let target_died = if let Some(target) = self.organisms.get_mut(&target_id) {
-                            target.record_damage_received(amount);
-                            target.consume_energy(amount);
-                            target.energy <= 0
-                        } else {
-                            false
-                        };
+    /// Eat a bite of `kind` (meat first if unset) from the organism's tile
+    fn eat(&mut self, id: OrganismId, kind: Option<ResourceKind>) {
+        let yields = self.food_yields();
+        let Some(organism) = self.organisms.get_mut(&id) else {
+            return;
+        };
+        let organism_pos = organism.position;
+        let mut grid = self.grid.write();
+        let tile = grid.get_mut(organism_pos);
 
-                        // Then update attacker
-                        if let Some(attacker) = self.organisms.get_mut(&id) {
-                            attacker.consume_energy(self.config.energy_config.attack_cost);
-                            attacker.record_damage_dealt(amount);
-                            if target_died {
-                                attacker.record_kill();
-                            }
-                        }
-                    }
-                }
-            }
+        // Without a preference, eat meat before it rots
+        let kind = kind.unwrap_or(if tile.meat_amount > 0 {
+            ResourceKind::Meat
+        } else {
+            ResourceKind::Plant
+        });
+        let food = self.config.resource_kind(kind);
+        let food_before = food_energy(tile, yields);
+        let consumed = tile.take(kind, food.bite_size);
+        self.energy_audit.food_eaten += food_before - food_energy(tile, yields);
+        drop(grid); // Release lock before mutating organism
+
+        if consumed > 0 {
+            let energy_gained = (consumed as f32 * food.energy_yield) as i32;
+            organism.add_energy(energy_gained);
+            self.energy_audit.eaten += energy_gained as i64;
+            organism.metrics.record_meal(kind, consumed);
+
+            let energy = organism.energy;
+            self.log_event(SimEvent::Eat {
+                organism_id: id,
+                position: organism_pos,
+                kind,
+                consumed,
+                energy,
+            });
+        }
+    }
 
+    fn apply_action(&mut self, id: OrganismId, action: Action) -> Result<()> {
+        let energy = self.organisms.get(&id).map_or(0, |o| o.energy);
+
+        match action {
             Action::Reproduce if self.config.dynamic_rules.allow_reproduction => {
                 self.reproduction_attempts += 1;
 
@@ -531,6 +858,11 @@ impl Simulation {
                     );
                 }
 
+                let age = self.organisms.get(&id).map_or(0, |o| o.age);
+                if failure_reason.is_none() && !self.config.energy_config.aging.is_fertile(age) {
+                    failure_reason = Some("outside_reproductive_window");
+                }
+
                 if failure_reason.is_none() && self.organisms.len() >= self.config.dynamic_rules.max_population {
                     failure_reason = Some("max_population_reached");
                     trace!(
@@ -559,149 +891,193 @@ impl Simulation {
                 // Get current population before any mutations
                 let current_population = self.organisms.len();
 
-                // Create offspring
-                if let Some(parent) = self.organisms.get_mut(&id) {
-                    let parent_energy_before = parent.energy;
-                    let parent_age = parent.age;
-                    let parent_birth_tick = parent.birth_tick;
-                    let parent_offspring_count = parent.metrics.offspring_count;
+                // Find empty adjacent cell; the parent only pays if there is one
+                let Some(parent_position) = self.organisms.get(&id).map(|p| p.position) else {
+                    return Ok(());
+                };
+                let free_tile = self.grid.read().free_neighbor(parent_position);
+                let Some(wrapped) = free_tile else {
+                    trace!(
+                        organism_id = ?id,
+                        tick = self.tick,
+                        position_x = parent_position.x,
+                        position_y = parent_position.y,
+                        "Reproduction failed: no empty adjacent cell found"
+                    );
 
-                    parent.consume_energy(self.config.energy_config.reproduce_cost);
-                    parent.record_offspring();
+                    event!(
+                        Level::DEBUG,
+                        counter_name = "reproduction_failures",
+                        counter_value = 1,
+                        failure_reason = "no_empty_adjacent_cell",
+                        organism_id = ?id,
+                        tick = self.tick,
+                        "Reproduction attempt failed"
+                    );
+                    return Ok(());
+                };
 
-                    let parent_position = parent.position;
-                    let parent_lineage = parent.lineage_id;
+                // Offspring of parents near radiation mutate faster
+                let mutation_scale = self.mutation_scale(parent_position);
+
+                let energy_config = &self.config.energy_config;
+                let policy = &energy_config.reproduction;
+                let parent = self.organisms.get_mut(&id).unwrap();
+                let parent_energy_before = parent.energy;
+                let parent_age = parent.age;
+                let parent_birth_tick = parent.birth_tick;
+                let parent_offspring_count = parent.metrics.offspring_count;
+                let parent_lineage = parent.lineage_id;
+
+                // The parent pays the cost, then funds its offspring
+                let cost = parent.drain_energy(energy_config.reproduce_cost);
+                let mut offspring_energy = policy.offspring_share(parent.energy);
+                parent.consume_energy(offspring_energy);
+                parent.record_offspring();
+                if policy.conserve_energy {
+                    offspring_energy += cost;
+                } else {
+                    self.energy_audit.actions += cost as i64;
+                }
 
-                    // Mutate genome
-                    let mut offspring_genome = parent.genome.clone();
+                let subsidy = policy.subsidy_at(self.tick).cloned().unwrap_or_default();
+                offspring_energy += subsidy.offspring.max(0);
+                parent.add_energy(subsidy.parent.max(0));
+                self.energy_audit.subsidized +=
+                    (subsidy.offspring.max(0) + subsidy.parent.max(0)) as i64;
+
+                // Mutate genome
+                let mut offspring_genome = parent.genome.clone();
+                if mutation_scale == 1.0 {
                     self.mutator.mutate(&mut offspring_genome, &mut self.rng);
+                } else {
+                    Mutator::new(self.mutator.config().scaled(mutation_scale))
+                        .mutate(&mut offspring_genome, &mut self.rng);
+                }
 
-                    // Find empty adjacent cell
-                    let (neighbors, width, height) = {
-                        let grid = self.grid.read();
-                        (grid.neighbors(parent_position, 1), grid.width, grid.height)
-                    };
-
-                    let mut offspring_spawned = false;
-                    for (neighbor_pos, tile) in neighbors {
-                        let wrapped = neighbor_pos.wrap(width, height);
-                        if tile.tile_type != TileType::Obstacle
-                            && !self.organism_positions.contains_key(&wrapped)
-                        {
-                            // Base offspring energy
-                            let base_offspring_energy = self.config.energy_config.initial_energy / 2;
-
-                            // MASSIVE BUFF: If parent was born after tick 1 and is successfully reproducing,
-                            // give offspring a huge energy bonus
-                            let buff_multiplier = if parent_birth_tick > 1 {
-                                5.0 // 5x energy bonus for offspring of successful reproducers!
-                            } else {
-                                1.0
-                            };
-
-                            let offspring_energy = (base_offspring_energy as f32 * buff_multiplier) as i32;
-
-                            // Spawn offspring
-                            let offspring = Organism::new_with_birth_tick(
-                                parent_lineage,
-                                wrapped,
-                                offspring_energy,
-                                offspring_genome.clone(),
-                                self.tick,
-                            );
-                            let offspring_id = offspring.id;
-
-                            // Get parent energy after reproduction
-                            let parent_energy_after = parent.energy;
-
-                            // Also give the PARENT a massive energy buff as a reward!
-                            let parent_final_energy = if parent_birth_tick > 1 {
-                                let parent_buff = 1000; // 1000 energy bonus for successful reproduction!
-                                parent.add_energy(parent_buff);
-                                let final_energy = parent.energy;
-
-                                info!(
-                                    event = "parent_reproduction_buff",
-                                    parent_id = ?id,
-                                    buff_amount = parent_buff,
-                                    new_parent_energy = final_energy,
-                                    tick = self.tick,
-                                    "🌟 Parent received massive energy buff for successful reproduction!"
-                                );
-                                final_energy
-                            } else {
-                                parent.energy
-                            };
-
-                            // COMPREHENSIVE LOGGING for successful reproduction
-                            info!(
-                                event = "reproduction_success",
-                                parent_id = ?id,
-                                offspring_id = ?offspring_id,
-                                lineage_id = ?parent_lineage,
-                                tick = self.tick,
-                                parent_age = parent_age,
-                                parent_birth_tick = parent_birth_tick,
-                                parent_energy_before = parent_energy_before,
-                                parent_energy_after = parent_energy_after,
-                                parent_final_energy = parent_final_energy,
-                                parent_offspring_count = parent_offspring_count + 1,
-                                offspring_energy = offspring_energy,
-                                buff_applied = parent_birth_tick > 1,
-                                buff_multiplier = buff_multiplier,
-                                parent_position_x = parent_position.x,
-                                parent_position_y = parent_position.y,
-                                offspring_position_x = wrapped.x,
-                                offspring_position_y = wrapped.y,
-                                population = current_population + 1,
-                                "🎉 Organism successfully reproduced!"
-                            );
-
-                            self.organism_positions.insert(wrapped, offspring_id);
-                            self.organisms.insert(offspring_id, offspring);
-                            self.reproduction_successes += 1;
-                            self.total_offspring_born += 1;
-                            offspring_spawned = true;
-
-                            // Record metrics
-                            event!(
-                                Level::INFO,
-                                counter_name = "reproductions_successful",
-                                counter_value = 1,
-                                parent_birth_tick = parent_birth_tick,
-                                buff_applied = parent_birth_tick > 1,
-                                "Reproduction success metric"
-                            );
-
-                            break;
-                        }
-                    }
+                // Spawn offspring
+                let mut offspring = Organism::new_offspring(
+                    &[&*parent],
+                    wrapped,
+                    offspring_energy,
+                    offspring_genome,
+                    self.tick,
+                );
+                offspring.id = OrganismId::from_random_bytes(self.rng.gen());
+                let offspring_id = offspring.id;
+                self.births.push(BirthRecord {
+                    mutation_scale,
+                    ..BirthRecord::from(&offspring)
+                });
+                if let Some(log) = self.event_log.as_mut() {
+                    log.record(SimEvent::Birth {
+                        organism_id: offspring_id,
+                        lineage_id: offspring.lineage_id,
+                        parent_ids: offspring.parent_ids.clone(),
+                        position: wrapped,
+                        energy: offspring_energy,
+                    });
+                }
 
-                    if !offspring_spawned {
-                        trace!(
-                            organism_id = ?id,
-                            tick = self.tick,
-                            position_x = parent_position.x,
-                            position_y = parent_position.y,
-                            "Reproduction failed: no empty adjacent cell found"
-                        );
-
-                        event!(
-                            Level::DEBUG,
-                            counter_name = "reproduction_failures",
-                            counter_value = 1,
-                            failure_reason = "no_empty_adjacent_cell",
-                            organism_id = ?id,
-                            tick = self.tick,
-                            "Reproduction attempt failed"
-                        );
-                    }
+                // COMPREHENSIVE LOGGING for successful reproduction
+                info!(
+                    event = "reproduction_success",
+                    parent_id = ?id,
+                    offspring_id = ?offspring_id,
+                    lineage_id = ?parent_lineage,
+                    tick = self.tick,
+                    parent_age = parent_age,
+                    parent_birth_tick = parent_birth_tick,
+                    parent_energy_before = parent_energy_before,
+                    parent_energy_after = parent.energy,
+                    parent_offspring_count = parent_offspring_count + 1,
+                    offspring_energy = offspring_energy,
+                    subsidy_offspring = subsidy.offspring,
+                    subsidy_parent = subsidy.parent,
+                    parent_position_x = parent_position.x,
+                    parent_position_y = parent_position.y,
+                    offspring_position_x = wrapped.x,
+                    offspring_position_y = wrapped.y,
+                    population = current_population + 1,
+                    "🎉 Organism successfully reproduced!"
+                );
+
+                self.grid.write().place(wrapped, offspring_id);
+                self.organisms.insert(offspring_id, offspring);
+                self.reproduction_successes += 1;
+                self.total_offspring_born += 1;
+
+                // Record metrics
+                event!(
+                    Level::INFO,
+                    counter_name = "reproductions_successful",
+                    counter_value = 1,
+                    parent_birth_tick = parent_birth_tick,
+                    "Reproduction success metric"
+                );
+            }
+
+            Action::ShareEnergy { slot, amount } if amount > 0 => {
+                let receiver = self
+                    .neighbor_in_slot(id, slot)
+                    .filter(|other| self.organisms[other].is_alive());
+                let Some(receiver_id) = receiver else {
+                    return Ok(());
+                };
+                let giver = self.organisms.get_mut(&id).unwrap();
+                let amount = amount.min(giver.energy.max(0));
+                if amount == 0 {
+                    return Ok(());
+                }
+                giver.consume_energy(amount);
+                let giver_lineage = giver.lineage_id;
+                let giver_energy = giver.energy;
+
+                let delivered =
+                    (amount as f32 * (1.0 - self.config.energy_config.share_loss.clamp(0.0, 1.0)))
+                        as i32;
+                self.energy_audit.sharing_loss += (amount - delivered) as i64;
+                let receiver = self.organisms.get_mut(&receiver_id).unwrap();
+                receiver.add_energy(delivered);
+                receiver.metrics.energy_received += delivered as i64;
+                let kin = receiver.lineage_id == giver_lineage;
+                if kin {
+                    receiver.metrics.kin_energy_received += delivered as i64;
+                }
+                let receiver_energy = receiver.energy;
+
+                let giver = self.organisms.get_mut(&id).unwrap();
+                giver.metrics.energy_given += amount as i64;
+                if kin {
+                    giver.metrics.kin_energy_given += amount as i64;
+                }
+
+                self.log_event(SimEvent::ShareEnergy {
+                    giver_id: id,
+                    receiver_id,
+                    amount,
+                    giver_energy,
+                    receiver_energy,
+                });
+            }
+
+            Action::Mate { slot }
+                if self.config.dynamic_rules.allow_reproduction
+                    && self.config.dynamic_rules.allow_mating =>
+            {
+                if let Some(partner) = self.neighbor_in_slot(id, slot) {
+                    self.mate_proposals.push((id, partner));
                 }
             }
 
             Action::EmitSignal { channel, value } => {
                 // Signals are recorded but not yet processed
                 debug!("Organism {:?} emitted signal {} on channel {}", id, value, channel);
+                self.log_event(SimEvent::Signal {
+                    organism_id: id,
+                    channel,
+                    value,
+                });
             }
 
             _ => {}
@@ -710,19 +1086,211 @@ impl Simulation {
         Ok(())
     }
 
+    /// Organism in one of an organism's 8 neighbor slots
+    fn neighbor_in_slot(&self, id: OrganismId, slot: i32) -> Option<OrganismId> {
+        let organism = self.organisms.get(&id)?;
+        let grid = self.grid.read();
+        grid.step(organism.position, Direction::from_slot(slot))
+            .and_then(|pos| grid.occupant(pos))
+            .filter(|&other| other != id)
+    }
+
+    /// `sense_neighbor` values for every slot: empty, kin or another lineage
+    fn neighbor_codes(&self, id: OrganismId) -> [i32; 8] {
+        let lineage_id = self.organisms[&id].lineage_id;
+        let mut codes = [NEIGHBOR_NONE; 8];
+        for (slot, code) in codes.iter_mut().enumerate() {
+            if let Some(other) = self.neighbor_in_slot(id, slot as i32) {
+                *code = if self.organisms[&other].lineage_id == lineage_id {
+                    NEIGHBOR_KIN
+                } else {
+                    NEIGHBOR_OTHER
+                };
+            }
+        }
+        codes
+    }
+
+    /// Pair up organisms that proposed to each other this tick.
+    ///
+    /// Pairs are taken in proposal order and each organism mates at most
+    /// once per tick.
+    fn resolve_matings(&mut self) {
+        let proposals = std::mem::take(&mut self.mate_proposals);
+        if proposals.is_empty() {
+            return;
+        }
+        let proposed: HashSet<(OrganismId, OrganismId)> = proposals.iter().copied().collect();
+        let mut mated = HashSet::new();
+
+        for &(a, b) in &proposals {
+            if mated.contains(&a) || mated.contains(&b) || !proposed.contains(&(b, a)) {
+                continue;
+            }
+            if self.organisms.len() >= self.config.dynamic_rules.max_population {
+                break;
+            }
+            if self.mate(a, b) {
+                mated.insert(a);
+                mated.insert(b);
+            }
+        }
+    }
+
+    /// Produce an offspring of two consenting organisms; false if either
+    /// can't afford it or there is no room next to them
+    fn mate(&mut self, a: OrganismId, b: OrganismId) -> bool {
+        let mating = self.config.energy_config.mating.clone();
+        let aging = &self.config.energy_config.aging;
+        let (pos_a, pos_b) = match (self.organisms.get(&a), self.organisms.get(&b)) {
+            (Some(pa), Some(pb))
+                if pa.is_alive()
+                    && pb.is_alive()
+                    && pa.energy >= mating.min_energy
+                    && pb.energy >= mating.min_energy
+                    && aging.is_fertile(pa.age)
+                    && aging.is_fertile(pb.age) =>
+            {
+                (pa.position, pb.position)
+            }
+            _ => return false,
+        };
+
+        let free_tile = {
+            let grid = self.grid.read();
+            grid.free_neighbor(pos_a).or_else(|| grid.free_neighbor(pos_b))
+        };
+        let Some(position) = free_tile else {
+            return false;
+        };
+
+        // Each parent pays the cost, then contributes a share of what is left
+        let mut offspring_energy = 0;
+        for id in [a, b] {
+            let parent = self.organisms.get_mut(&id).unwrap();
+            self.energy_audit.actions += parent.drain_energy(mating.cost) as i64;
+            let share = (parent.energy.max(0) as f32 * mating.contribution) as i32;
+            parent.consume_energy(share);
+            parent.record_offspring();
+            offspring_energy += share;
+        }
+
+        let mutation_scale = self.mutation_scale(pos_a);
+        let mut genome = {
+            let (pa, pb) = (&self.organisms[&a], &self.organisms[&b]);
+            self.mutator.crossover(&pa.genome, &pb.genome, &mut self.rng)
+        };
+        if mutation_scale == 1.0 {
+            self.mutator.mutate(&mut genome, &mut self.rng);
+        } else {
+            Mutator::new(self.mutator.config().scaled(mutation_scale))
+                .mutate(&mut genome, &mut self.rng);
+        }
+
+        let mut offspring = Organism::new_offspring(
+            &[&self.organisms[&a], &self.organisms[&b]],
+            position,
+            offspring_energy,
+            genome,
+            self.tick,
+        );
+        offspring.id = OrganismId::from_random_bytes(self.rng.gen());
+        let offspring_id = offspring.id;
+
+        self.births.push(BirthRecord {
+            mutation_scale,
+            ..BirthRecord::from(&offspring)
+        });
+        self.log_event(SimEvent::Birth {
+            organism_id: offspring_id,
+            lineage_id: offspring.lineage_id,
+            parent_ids: offspring.parent_ids.clone(),
+            position,
+            energy: offspring_energy,
+        });
+        debug!(
+            event = "mating_success",
+            parent_a = ?a,
+            parent_b = ?b,
+            offspring_id = ?offspring_id,
+            offspring_energy,
+            tick = self.tick,
+            "Organisms mated"
+        );
+
+        self.grid.write().place(position, offspring_id);
+        self.organisms.insert(offspring_id, offspring);
+        self.reproduction_successes += 1;
+        self.total_offspring_born += 1;
+        true
+    }
+
+    /// Mutation rate factor for offspring of a parent at `pos`: one plus
+    /// the strongest radiation within reach
+    fn mutation_scale(&self, pos: Position) -> f32 {
+        let reach = self.config.world_config.radiation.reach.max(0);
+        let grid = self.grid.read();
+        let radiation = grid
+            .neighborhood(pos, reach)
+            .map(|cell| cell.tile.radiation)
+            .fold(grid.get(pos).radiation, f32::max);
+        1.0 + radiation.max(0.0)
+    }
+
+    /// Energy per unit of plants and meat
+    fn food_yields(&self) -> (f64, f64) {
+        (
+            self.config.resource_kind(ResourceKind::Plant).energy_yield as f64,
+            self.config.resource_kind(ResourceKind::Meat).energy_yield as f64,
+        )
+    }
+
+    /// Food energy on each tile, in grid order
+    fn food_by_tile(&self) -> Vec<i64> {
+        let yields = self.food_yields();
+        self.grid
+            .read()
+            .iter()
+            .map(|(_, tile)| food_energy(tile, yields))
+            .collect()
+    }
+
+    /// Food energy added and removed across tiles since `before` was taken,
+    /// and the current food energy of each tile
+    fn food_changes(&self, before: &[i64]) -> (i64, i64, Vec<i64>) {
+        let after = self.food_by_tile();
+        let (mut added, mut removed) = (0, 0);
+        for (before, after) in before.iter().zip(&after) {
+            if after > before {
+                added += after - before;
+            } else {
+                removed += before - after;
+            }
+        }
+        (added, removed, after)
+    }
+
+    /// Fill in the end-of-tick totals and check the books balance
+    fn close_energy_audit(&mut self) {
+        let food = self.food_by_tile().iter().sum();
+
+        let audit = &mut self.energy_audit;
+        audit.end = self.organisms.values().map(|o| o.energy as i64).sum();
+        audit.food = food;
+        if audit.discrepancy() != 0 || audit.system_discrepancy() != 0 {
+            warn!(tick = self.tick, audit = ?audit, "Energy audit does not balance");
+        } else {
+            trace!(tick = self.tick, audit = ?audit, "Energy audit");
+        }
+    }
+
     fn apply_hazards(&mut self) {
         let hazard_damage = self.config.world_config.hazard_damage;
 
-        for (pos, id) in self.organism_positions.iter() {
-            let is_hazard = {
-                let grid = self.grid.read();
-                grid.get(*pos).tile_type == TileType::Hazard
-            };
-
-            if is_hazard {
-                if let Some(organism) = self.organisms.get_mut(id) {
-                    organism.consume_energy(hazard_damage);
-                }
+        let grid = self.grid.read();
+        for organism in self.organisms.values_mut() {
+            if grid.get(organism.position).tile_type == TileType::Hazard {
+                self.energy_audit.hazards += organism.drain_energy(hazard_damage) as i64;
             }
         }
     }
@@ -735,9 +1303,30 @@ impl Simulation {
             .map(|(id, _)| *id)
             .collect();
 
+        let meat_yield = self.config.resource_kind(ResourceKind::Meat).energy_yield;
+        let yields = self.food_yields();
+
         for id in dead {
             if let Some(organism) = self.organisms.remove(&id) {
-                self.organism_positions.remove(&organism.position);
+                self.grid.write().vacate(organism.position);
+                self.energy_audit.removed += organism.energy as i64;
+
+                let carcass = self
+                    .config
+                    .world_config
+                    .carcass
+                    .meat_for(organism.tick_start_energy, meat_yield);
+                {
+                    let mut grid = self.grid.write();
+                    let tile = grid.get_mut(organism.position);
+                    let food_before = food_energy(tile, yields);
+                    tile.meat_amount += carcass;
+                    self.energy_audit.carcasses += food_energy(tile, yields) - food_before;
+                }
+                self.log_event(SimEvent::Death {
+                    organism_id: id,
+                    carcass,
+                });
 
                 // Log death with comprehensive details
                 let was_born_after_tick_1 = organism.birth_tick > 1;
@@ -788,37 +1377,82 @@ impl Simulation {
         }
     }
 
-    fn spawn_organism(&mut self, lineage_id: LineageId, genome: Program) -> Result<()> {
-        // Find random empty position
-        let (width, height) = {
-            let grid = self.grid.read();
-            (grid.width, grid.height)
-        };
+    /// Move this round's migrants to free tiles in their new regions.
+    /// Migrants that find no room stay where they are.
+    fn migrate(&mut self) {
+        let mut residents: Vec<(OrganismId, Position)> = self
+            .organisms
+            .values()
+            .map(|org| (org.id, org.position))
+            .collect();
+        residents.sort_unstable_by_key(|(id, _)| id.0);
+
+        for migration in self.regions.plan_migration(residents, &mut self.rng) {
+            let mut grid = self.grid.write();
+            let Some(to) = self.regions.free_tile(&grid, migration.to, &mut self.rng) else {
+                continue;
+            };
+            let organism = self.organisms.get_mut(&migration.id).unwrap();
+            grid.vacate(organism.position);
+            grid.place(to, organism.id);
+            organism.position = to;
+            drop(grid);
+
+            self.migration_counts[migration.from].1 += 1;
+            self.migration_counts[migration.to].0 += 1;
+            self.log_event(SimEvent::Migrate {
+                organism_id: migration.id,
+                to,
+            });
+        }
+    }
+
+    fn spawn_organism(
+        &mut self,
+        lineage_id: LineageId,
+        genome: Program,
+        region: usize,
+    ) -> Result<()> {
+        // Find random empty position in the region
+        let columns = self.regions.columns(region);
+        let height = self.grid.read().height;
+        let zones: Vec<Position> = self
+            .spawn_zones
+            .iter()
+            .copied()
+            .filter(|&pos| self.regions.region_of(pos) == region)
+            .collect();
+        let zones = if zones.is_empty() { self.spawn_zones.clone() } else { zones };
 
         for _ in 0..100 {
-            let x = self.rng.gen_range(0..width);
-            let y = self.rng.gen_range(0..height);
-            let pos = Position::new(x, y);
-
-            if !self.organism_positions.contains_key(&pos) {
-                let tile_type = {
-                    let grid = self.grid.read();
-                    grid.get(pos).tile_type
-                };
+            let pos = if zones.is_empty() {
+                let x = self.rng.gen_range(columns.clone());
+                let y = self.rng.gen_range(0..height);
+                Position::new(x, y)
+            } else {
+                *zones.choose(&mut self.rng).unwrap()
+            };
 
-                if tile_type != TileType::Obstacle {
-                    let organism = Organism::new_with_birth_tick(
-                        lineage_id,
-                        pos,
-                        self.config.energy_config.initial_energy,
-                        genome,
-                        self.tick,
-                    );
-                    let id = organism.id;
-                    self.organism_positions.insert(pos, id);
-                    self.organisms.insert(id, organism);
-                    return Ok(());
-                }
+            let cell_free = self.grid.read().is_free(pos);
+            if cell_free {
+                let mut organism = Organism::new_with_birth_tick(
+                    lineage_id,
+                    pos,
+                    self.config.energy_config.initial_energy,
+                    genome,
+                    self.tick,
+                );
+                organism.id = OrganismId::from_random_bytes(self.rng.gen());
+                let id = organism.id;
+                self.log_event(SimEvent::Spawn {
+                    organism_id: id,
+                    lineage_id,
+                    position: pos,
+                    energy: organism.energy,
+                });
+                self.grid.write().place(pos, id);
+                self.organisms.insert(id, organism);
+                return Ok(());
             }
         }
 
@@ -830,6 +1464,10 @@ impl Simulation {
         let mut survivors: Vec<OrganismData> = Vec::new();
 
         // Finalize all organisms
+        let (width, height) = {
+            let grid = self.grid.read();
+            (grid.width, grid.height)
+        };
         for organism in self.organisms.values_mut() {
             organism.finalize_metrics(self.config.energy_config.initial_energy);
 
@@ -838,13 +1476,183 @@ impl Simulation {
                 .or_insert_with(Vec::new)
                 .push(organism.metrics.clone());
 
-            survivors.push(OrganismData::from(&*organism));
+            let mut data = OrganismData::from(&*organism);
+            data.behavior = organism.behavior.characterize(organism.position, width, height);
+            survivors.push(data);
         }
 
+        let region_stats = if self.config.world_config.regions.enabled() {
+            let mut regions: Vec<RegionStats> = self
+                .migration_counts
+                .iter()
+                .map(|&(immigrants, emigrants)| RegionStats {
+                    immigrants,
+                    emigrants,
+                    ..Default::default()
+                })
+                .collect();
+            for organism in self.organisms.values() {
+                regions[self.regions.region_of(organism.position)]
+                    .lineage_stats
+                    .entry(organism.lineage_id)
+                    .or_default()
+                    .push(organism.metrics.clone());
+            }
+            regions
+        } else {
+            Vec::new()
+        };
+
         SimulationResult {
             lineage_stats,
             survivors,
+            births: self.births.clone(),
             total_ticks: self.tick,
+            region_stats,
+        }
+    }
+}
+
+/// An organism's turn in the current tick
+struct Turn {
+    id: OrganismId,
+    /// Seed for the init call, if the organism has no instance yet
+    init_seed: Option<u64>,
+    /// Neighbor codes at the start of the tick
+    neighbors: [i32; 8],
+}
+
+/// What organisms want to do this tick, in turn order
+#[derive(Default)]
+struct Intents {
+    moves: Vec<MoveIntent>,
+    /// Attacker, target slot and damage
+    attacks: Vec<(Claimant, i32, i32)>,
+    meals: Vec<MealIntent>,
+    /// Actions applied one at a time after moving and eating
+    other: Vec<(OrganismId, Action)>,
+}
+
+/// What an organism's WASM step produced
+enum StepOutcome {
+    Acted { actions: Vec<Action>, fuel_used: u64 },
+    /// The genome doesn't compile or instantiate
+    NotViable(Error),
+    /// The step trapped or ran out of fuel
+    Failed(Error),
+}
+
+/// Builds organism instances; shared between threads in the parallel phase
+struct InstanceFactory<'a> {
+    compiler: &'a Compiler,
+    runtime: &'a Runtime,
+    grid: &'a Arc<RwLock<Grid>>,
+    plant_code: i32,
+    meat_code: i32,
+}
+
+impl<'a> InstanceFactory<'a> {
+    fn new(
+        compiler: &'a Compiler,
+        runtime: &'a Runtime,
+        grid: &'a Arc<RwLock<Grid>>,
+        config: &JobConfig,
+    ) -> Self {
+        Self {
+            compiler,
+            runtime,
+            grid,
+            plant_code: config.resource_kind(ResourceKind::Plant).sense_code,
+            meat_code: config.resource_kind(ResourceKind::Meat).sense_code,
+        }
+    }
+
+    fn build(&self, organism: &Organism) -> Result<OrganismInstance> {
+        let wasm_bytes = self.compiler.compile(&organism.genome)?;
+
+        // Create context with environment query
+        let grid_clone = self.grid.clone();
+        let (plant_code, meat_code) = (self.plant_code, self.meat_code);
+        let env_query = Arc::new(move |x: i32, y: i32| {
+            let grid = grid_clone.read();
+            let tile = grid.get(Position::new(x, y));
+            // Meat lies on top of whatever the tile is
+            if tile.meat_amount > 0 {
+                return meat_code;
+            }
+            match tile.tile_type {
+                TileType::Empty => 0,
+                TileType::Resource => plant_code,
+                TileType::Obstacle => 2,
+                TileType::Hazard => 3,
+            }
+        });
+
+        let context = Arc::new(OrganismContext::new(
+            organism.id,
+            organism.energy,
+            organism.position,
+            env_query,
+        ));
+        let host_functions = HostFunctions::new(context);
+
+        self.runtime.instantiate(&wasm_bytes, host_functions)
+    }
+}
+
+/// Instantiate an organism if needed and run its step with fresh sensors
+fn run_turn(factory: &InstanceFactory, organism: &mut Organism, turn: &Turn) -> StepOutcome {
+    if let Some(seed) = turn.init_seed {
+        match factory
+            .build(organism)
+            .and_then(|mut instance| instance.init(seed).map(|_| instance))
+        {
+            Ok(instance) => organism.instance = Some(instance),
+            Err(e) => return StepOutcome::NotViable(e),
+        }
+    }
+
+    let Some(instance) = organism.instance.as_mut() else {
+        return StepOutcome::NotViable(Error::Other("no instance".to_string()));
+    };
+    let context = &instance.host_functions().context;
+    context.update_sensors(organism.energy, organism.age, organism.position);
+    context.set_neighbors(turn.neighbors);
+    match instance.step(0) {
+        Ok((_, actions)) => StepOutcome::Acted {
+            actions,
+            fuel_used: instance.fuel_consumed(),
+        },
+        Err(e) => StepOutcome::Failed(e),
+    }
+}
+
+/// Genealogy entry for an organism born during the simulation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BirthRecord {
+    pub organism_id: OrganismId,
+    pub lineage_id: LineageId,
+    pub parent_ids: Vec<OrganismId>,
+    pub generation: u32,
+    pub tick: u64,
+    /// Factor the mutation rates were scaled by (1.0 away from radiation)
+    #[serde(default = "default_mutation_scale")]
+    pub mutation_scale: f32,
+}
+
+fn default_mutation_scale() -> f32 {
+    1.0
+}
+
+impl From<&Organism> for BirthRecord {
+    fn from(org: &Organism) -> Self {
+        Self {
+            organism_id: org.id,
+            lineage_id: org.lineage_id,
+            parent_ids: org.parent_ids.clone(),
+            generation: org.generation,
+            tick: org.birth_tick,
+            mutation_scale: 1.0,
         }
     }
 }
@@ -853,7 +1661,115 @@ impl Simulation {
 pub struct SimulationResult {
     pub lineage_stats: HashMap<LineageId, Vec<FitnessMetrics>>,
     pub survivors: Vec<OrganismData>,
+    /// Every in-world birth, in order
+    #[serde(default)]
+    pub births: Vec<BirthRecord>,
     pub total_ticks: u64,
+    /// Survivors by region, when the world is divided into regions
+    #[serde(default)]
+    pub region_stats: Vec<RegionStats>,
+}
+
+/// Survivors and migration counts of one region
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegionStats {
+    pub lineage_stats: HashMap<LineageId, Vec<FitnessMetrics>>,
+    /// Organisms that arrived from other regions
+    pub immigrants: u64,
+    /// Organisms that left for other regions
+    pub emigrants: u64,
+}
+
+/// Food energy on a tile, at the given plant and meat yields
+fn food_energy(tile: &Tile, (plant, meat): (f64, f64)) -> i64 {
+    (tile.amount(ResourceKind::Plant) as f64 * plant) as i64
+        + (tile.amount(ResourceKind::Meat) as f64 * meat) as i64
+}
+
+/// Where organism and food energy came from and went during one tick.
+///
+/// Transfers between organisms (births, mating, sharing) cancel out, so
+/// `end - start` equals inflows minus outflows. Food is booked the same
+/// way, tile by tile, so the whole system's change (`total`) is explained
+/// by what was created and destroyed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnergyAudit {
+    pub tick: u64,
+    /// Energy held by organisms when the tick started
+    pub start: i64,
+    /// Energy held by organisms when the tick ended
+    pub end: i64,
+    /// Gained from food
+    pub eaten: i64,
+    /// Created by reproduction subsidies
+    pub subsidized: i64,
+    /// Basal and instruction costs
+    pub metabolism: i64,
+    /// Move, attack, reproduction and mating costs
+    pub actions: i64,
+    /// Damage dealt in attacks
+    pub combat: i64,
+    /// Damage from hazard tiles
+    pub hazards: i64,
+    /// Lost in transit when sharing energy
+    pub sharing_loss: i64,
+    /// Left in organisms that died or weren't viable
+    pub removed: i64,
+    /// Energy stored as plants and meat on tiles at the end of the tick
+    pub food: i64,
+    /// Energy stored as food when the tick started
+    pub food_start: i64,
+    /// Food grown back by plants
+    pub regrown: i64,
+    /// Food lost to rot (meat decay, plants on a `Decay` curve)
+    pub rotted: i64,
+    /// Meat left by organisms that died
+    pub carcasses: i64,
+    /// Food placed by scheduled relayouts and yield changes
+    pub relayout_added: i64,
+    /// Food removed by scheduled relayouts (including obstacles placed on
+    /// food) and yield changes
+    pub relayout_removed: i64,
+    /// Food taken off tiles by eating; differs from `eaten` by rounding
+    pub food_eaten: i64,
+}
+
+impl EnergyAudit {
+    pub fn inflow(&self) -> i64 {
+        self.eaten + self.subsidized
+    }
+
+    pub fn outflow(&self) -> i64 {
+        self.metabolism + self.actions + self.combat + self.hazards + self.sharing_loss + self.removed
+    }
+
+    /// Energy change the flows don't explain; zero when the books balance
+    pub fn discrepancy(&self) -> i64 {
+        self.end - self.start - self.inflow() + self.outflow()
+    }
+
+    /// Food change the food flows don't explain
+    pub fn food_discrepancy(&self) -> i64 {
+        self.food - self.food_start - self.regrown - self.carcasses - self.relayout_added
+            + self.rotted
+            + self.relayout_removed
+            + self.food_eaten
+    }
+
+    /// Change in `total` that nothing created or destroyed accounts for;
+    /// zero when organisms and food both balance
+    pub fn system_discrepancy(&self) -> i64 {
+        let created = self.subsidized + self.regrown + self.carcasses + self.relayout_added;
+        let destroyed = self.outflow() + self.rotted + self.relayout_removed;
+        // Eating moves energy from food to organisms, up to rounding
+        let digestion = self.food_eaten - self.eaten;
+        self.total() - self.start - self.food_start - created + destroyed + digestion
+    }
+
+    /// Energy in organisms plus food at the end of the tick
+    pub fn total(&self) -> i64 {
+        self.end + self.food
+    }
 }
 
 #[cfg(test)]
@@ -885,6 +1801,606 @@ mod tests {
         program
     }
 
+    /// Genome that moves right, eats and tries to reproduce every step
+    fn create_forager_genome() -> Program {
+        let mut program = create_test_genome();
+        let step = &mut program.functions[1];
+        step.num_locals = 4;
+
+        let block = step.get_block_mut(0).unwrap();
+        block.instructions = vec![
+            Instruction::load_const(Register(0), Value::Int(1)),
+            Instruction::load_const(Register(1), Value::Int(0)),
+            Instruction::new(Opcode::Move)
+                .with_operands(vec![Operand::Register(Register(0)), Operand::Register(Register(1))]),
+            Instruction::new(Opcode::Eat).with_dest(Register(2)),
+            Instruction::new(Opcode::Reproduce).with_dest(Register(3)),
+            Instruction::return_value(Register(2)),
+        ];
+
+        program
+    }
+
+    /// Moves `dx` tiles east every step
+    fn create_mover_genome(dx: i32) -> Program {
+        let mut program = create_test_genome();
+        let step = &mut program.functions[1];
+        step.num_locals = 2;
+        step.get_block_mut(0).unwrap().instructions = vec![
+            Instruction::load_const(Register(0), Value::Int(dx)),
+            Instruction::load_const(Register(1), Value::Int(0)),
+            Instruction::new(Opcode::Move)
+                .with_operands(vec![Operand::Register(Register(0)), Operand::Register(Register(1))]),
+            Instruction::return_value(Register(1)),
+        ];
+        program
+    }
+
+    #[test]
+    fn test_replay_matches_simulation() {
+        use crate::event_log::Replay;
+        use std::sync::Mutex;
+
+        #[derive(Clone, Default)]
+        struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for SharedBuf {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut config = JobConfig {
+            num_ticks: 60,
+            seed: 7,
+            ..Default::default()
+        };
+        config.world_config.width = 24;
+        config.world_config.height = 24;
+        // Re-lay the world mid-run; the replay has to follow
+        config.schedule.events.push(evo_core::ScheduledEvent {
+            tick: 30,
+            changes: evo_core::EnvironmentChange {
+                resource_regen_rate: Some(0.5),
+                resource_density: Some(0.3),
+                obstacle_density: Some(0.1),
+                ..Default::default()
+            },
+        });
+
+        let genomes = vec![
+            (LineageId::new(), create_forager_genome()),
+            (LineageId::new(), create_forager_genome()),
+        ];
+        let mut sim = Simulation::new(config, genomes).unwrap();
+
+        let buf = SharedBuf::default();
+        sim.record_events(buf.clone()).unwrap();
+        sim.run().unwrap();
+
+        let bytes = buf.0.lock().unwrap().clone();
+        let replay = Replay::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(replay.frames().len(), 60);
+        assert_eq!(replay.last_tick(), Some(59));
+
+        let state = replay.state_at(59);
+        assert_eq!(state.organisms.len(), sim.organisms.len());
+        for (id, organism) in &sim.organisms {
+            let replayed = &state.organisms[id];
+            assert_eq!(replayed.position, organism.position);
+            assert_eq!(replayed.lineage_id, organism.lineage_id);
+        }
+
+        let grid = sim.grid.read();
+        for ((_, expected), (_, replayed)) in grid.iter().zip(state.grid.iter()) {
+            assert_eq!(expected.tile_type, replayed.tile_type);
+            assert_eq!(expected.resource_amount, replayed.resource_amount);
+            assert_eq!(expected.meat_amount, replayed.meat_amount);
+        }
+
+        let births = replay
+            .frames()
+            .iter()
+            .flat_map(|f| &f.events)
+            .filter(|e| matches!(e, SimEvent::Birth { .. }))
+            .count();
+        assert_eq!(births, sim.births.len());
+    }
+
+    #[test]
+    fn test_snapshot_resume_matches_uninterrupted_run() {
+        let mut config = JobConfig {
+            num_ticks: 40,
+            seed: 11,
+            ..Default::default()
+        };
+        config.world_config.width = 24;
+        config.world_config.height = 24;
+
+        let genomes = vec![(LineageId::new(), create_forager_genome())];
+        let mut original = Simulation::new(config, genomes).unwrap();
+        original.run_until(20).unwrap();
+
+        let bytes = original.snapshot().to_bytes().unwrap();
+        let mut resumed = Simulation::restore(SimulationSnapshot::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(resumed.tick(), 19);
+
+        let expected = original.run().unwrap();
+        let actual = resumed.run().unwrap();
+
+        assert_eq!(actual.total_ticks, expected.total_ticks);
+        assert_eq!(actual.births.len(), expected.births.len());
+        assert_eq!(resumed.organisms.len(), original.organisms.len());
+        for (id, organism) in &original.organisms {
+            let other = &resumed.organisms[id];
+            assert_eq!(other.position, organism.position);
+            assert_eq!(other.energy, organism.energy);
+            assert_eq!(other.age, organism.age);
+            assert_eq!(other.behavior, organism.behavior);
+        }
+        assert_eq!(RngState::from(&resumed.rng), RngState::from(&original.rng));
+    }
+
+    #[test]
+    fn test_map_sets_world_and_spawn_zones() {
+        let mut config = JobConfig::default();
+        config.world_config.map = Some(evo_core::MapSource::Inline(
+            "#####\n#S.*#\n#####\n".to_string(),
+        ));
+
+        let sim = Simulation::new(config, vec![(LineageId::new(), create_test_genome())]).unwrap();
+
+        assert_eq!(sim.config.world_config.width, 5);
+        assert_eq!(sim.config.world_config.height, 3);
+        let organism = sim.organisms.values().next().unwrap();
+        assert_eq!(organism.position, Position::new(1, 1));
+    }
+
+    #[test]
+    fn test_eat_kind_tracks_diet() {
+        let mut genome = create_test_genome();
+        let step = &mut genome.functions[1];
+        step.num_locals = 2;
+        step.get_block_mut(0).unwrap().instructions = vec![
+            Instruction::load_const(Register(0), Value::Int(1)),
+            Instruction::new(Opcode::EatKind)
+                .with_operand(Operand::Register(Register(0)))
+                .with_dest(Register(1)),
+            Instruction::return_value(Register(1)),
+        ];
+
+        let mut config = JobConfig::default();
+        config.world_config.map = Some(evo_core::MapSource::Inline(
+            "####\n#S*#\n####\n".to_string(),
+        ));
+        let mut sim = Simulation::new(config, vec![(LineageId::new(), genome)]).unwrap();
+        sim.grid.write().get_mut(Position::new(1, 1)).meat_amount = 300;
+
+        sim.run_until(3).unwrap();
+
+        // Two bites of decaying meat: 200 of 297, then the remaining 96
+        let organism = sim.organisms.values().next().unwrap();
+        assert_eq!(organism.metrics.meat_eaten, 296);
+        assert_eq!(organism.metrics.plant_eaten, 0);
+        assert_eq!(organism.metrics.meat_fraction(), 1.0);
+        assert_eq!(sim.grid.read().get(Position::new(1, 1)).meat_amount, 0);
+    }
+
+    #[test]
+    fn test_death_leaves_carcass() {
+        let mut config = JobConfig::default();
+        config.world_config.hazard_damage = 10_000;
+        config.world_config.map = Some(evo_core::MapSource::Inline(
+            "###\n#S#\n###\n".to_string(),
+        ));
+        let mut sim =
+            Simulation::new(config, vec![(LineageId::new(), create_test_genome())]).unwrap();
+        sim.grid.write().set(Position::new(1, 1), evo_core::Tile::hazard());
+        sim.organisms.values_mut().next().unwrap().energy = 500;
+
+        sim.run_until(1).unwrap();
+
+        // Half of the 500 energy at 2.0 energy per unit, plus the body mass
+        assert!(sim.organisms.is_empty());
+        assert_eq!(sim.grid.read().get(Position::new(1, 1)).meat_amount, 125 + 100);
+    }
+
+    #[test]
+    fn test_radiation_scales_mutation_per_birth() {
+        let mut config = JobConfig {
+            seed: 11,
+            ..Default::default()
+        };
+        config.world_config.width = 16;
+        config.world_config.height = 16;
+        config.world_config.radiation = evo_core::RadiationConfig {
+            density: 1.0,
+            layout: evo_core::TileLayout::Uniform,
+            level: 2.0,
+            reach: 1,
+        };
+
+        let mut sim =
+            Simulation::new(config, vec![(LineageId::new(), create_forager_genome())]).unwrap();
+        sim.run_until(10).unwrap();
+
+        assert!(!sim.births.is_empty());
+        assert!(sim.births.iter().all(|b| b.mutation_scale == 3.0));
+    }
+
+    #[test]
+    fn test_energy_audit_balances_each_tick() {
+        let mut config = JobConfig {
+            seed: 5,
+            ..Default::default()
+        };
+        config.world_config.width = 16;
+        config.world_config.height = 16;
+        config.energy_config.reproduction.subsidies.push(evo_core::Subsidy {
+            offspring: 100,
+            parent: 50,
+            ..Default::default()
+        });
+        let genomes = (0..4)
+            .map(|_| (LineageId::new(), create_forager_genome()))
+            .collect();
+
+        let mut sim = Simulation::new(config, genomes).unwrap();
+        let mut subsidized = 0;
+        for tick in 1..=20 {
+            sim.run_until(tick).unwrap();
+            let audit = sim.energy_audit();
+            assert_eq!(audit.discrepancy(), 0, "tick {}: {:?}", tick - 1, audit);
+            assert_eq!(audit.system_discrepancy(), 0, "tick {}: {:?}", tick - 1, audit);
+            subsidized += audit.subsidized;
+        }
+        assert!(sim.total_offspring_born > 0);
+        assert_eq!(subsidized, 150 * sim.total_offspring_born as i64);
+    }
+
+    #[test]
+    fn test_energy_audit_books_food() {
+        use evo_core::{EnvironmentChange, ScheduledEvent};
+
+        let mut config = JobConfig {
+            seed: 9,
+            ..Default::default()
+        };
+        config.world_config.width = 16;
+        config.world_config.height = 16;
+        config.world_config.obstacle_density = 0.3;
+        config.world_config.carcass.body_mass = 50;
+        // Organisms starve within a few ticks and leave carcasses
+        config.energy_config.initial_energy = 2 * config.energy_config.basal_cost;
+        config.schedule.events.push(ScheduledEvent {
+            tick: 3,
+            changes: EnvironmentChange {
+                obstacle_density: Some(0.3),
+                ..Default::default()
+            },
+        });
+        let genomes = (0..4)
+            .map(|_| (LineageId::new(), create_test_genome()))
+            .collect();
+
+        let mut sim = Simulation::new(config, genomes).unwrap();
+        let mut previous_total = None;
+        let (mut carcasses, mut relaid) = (0, 0);
+        for tick in 1..=6 {
+            sim.run_until(tick).unwrap();
+            let audit = sim.energy_audit();
+            assert_eq!(audit.food_discrepancy(), 0, "tick {}: {:?}", tick - 1, audit);
+            assert_eq!(audit.system_discrepancy(), 0, "tick {}: {:?}", tick - 1, audit);
+            // Nothing happens to the books between ticks
+            if let Some(total) = previous_total {
+                assert_eq!(audit.start + audit.food_start, total);
+            }
+            previous_total = Some(audit.total());
+            carcasses += audit.carcasses;
+            relaid += audit.relayout_removed;
+        }
+        assert!(carcasses > 0);
+        assert!(relaid > 0);
+    }
+
+    #[test]
+    fn test_conserving_reproduction_moves_cost_to_offspring() {
+        let mut config = JobConfig {
+            seed: 5,
+            ..Default::default()
+        };
+        config.world_config.width = 16;
+        config.world_config.height = 16;
+        config.world_config.hazard_damage = 0;
+        config.energy_config.reproduction = evo_core::ReproductionPolicy {
+            offspring_energy: evo_core::OffspringEnergy::Fixed(0),
+            subsidies: vec![evo_core::Subsidy {
+                offspring: 1000,
+                ..Default::default()
+            }],
+            conserve_energy: true,
+        };
+
+        let mut sim =
+            Simulation::new(config, vec![(LineageId::new(), create_forager_genome())]).unwrap();
+        sim.run_until(1).unwrap();
+
+        assert_eq!(sim.total_offspring_born, 1);
+        assert_eq!(sim.energy_audit().subsidized, 0);
+        let offspring = sim.organisms.values().find(|o| o.birth_tick == 0 && o.generation > 0);
+        assert_eq!(offspring.unwrap().energy, 300);
+    }
+
+    #[test]
+    fn test_aging_limits_lifespan_and_fertility() {
+        let mut config = JobConfig {
+            seed: 3,
+            ..Default::default()
+        };
+        config.world_config.width = 16;
+        config.world_config.height = 16;
+        config.energy_config.aging = evo_core::AgingConfig {
+            max_lifespan: Some(8),
+            fertile_from: 4,
+            ..Default::default()
+        };
+
+        let mut sim =
+            Simulation::new(config, vec![(LineageId::new(), create_forager_genome())]).unwrap();
+        // Organisms are one tick old after their first tick
+        sim.run_until(3).unwrap();
+        assert_eq!(sim.total_offspring_born, 0);
+
+        sim.run_until(4).unwrap();
+        assert!(sim.total_offspring_born > 0);
+        assert!(sim.organisms.values().all(|o| o.age <= 8));
+        assert_eq!(sim.energy_audit().discrepancy(), 0);
+
+        // The founder dies in its ninth tick
+        sim.run_until(9).unwrap();
+        assert!(sim.organisms.values().all(|o| o.birth_tick > 0));
+    }
+
+    #[test]
+    fn test_results_independent_of_thread_count() {
+        let run = |threads| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| {
+                let mut config = JobConfig {
+                    seed: 21,
+                    ..Default::default()
+                };
+                config.world_config.width = 16;
+                config.world_config.height = 16;
+                let genomes = (0..12)
+                    .map(|i| {
+                        let genome = if i % 2 == 0 {
+                            create_forager_genome()
+                        } else {
+                            create_test_genome()
+                        };
+                        (LineageId::new(), genome)
+                    })
+                    .collect();
+
+                let mut sim = Simulation::new(config, genomes).unwrap();
+                sim.run_until(30).unwrap();
+                let mut organisms: Vec<_> = sim
+                    .organisms
+                    .values()
+                    .map(|o| (o.id.0, o.position, o.energy, o.age))
+                    .collect();
+                organisms.sort_by_key(|o| o.0);
+                let births: Vec<_> = sim.births.iter().map(|b| b.organism_id).collect();
+                (organisms, births)
+            })
+        };
+
+        let serial = run(1);
+        assert!(!serial.1.is_empty());
+        assert_eq!(serial, run(4));
+    }
+
+    #[test]
+    fn test_contested_move_goes_to_richer_organism() {
+        let mut config = JobConfig::default();
+        config.world_config.width = 8;
+        config.world_config.height = 8;
+        config.world_config.hazard_damage = 0;
+        let (east, west) = (LineageId::new(), LineageId::new());
+        let mut sim = Simulation::new(config, vec![(east, create_mover_genome(1)), (west, create_mover_genome(-1))]).unwrap();
+
+        // Both try to step onto (3, 3); the east-mover has more energy
+        sim.grid.write().clear_occupants();
+        let mut rich = None;
+        for organism in sim.organisms.values_mut() {
+            let x = if organism.lineage_id == east {
+                organism.energy += 500;
+                rich = Some(organism.id);
+                2
+            } else {
+                4
+            };
+            organism.position = Position::new(x, 3);
+            sim.grid.write().place(organism.position, organism.id);
+            sim.grid.write().get_mut(Position::new(x, 3)).tile_type = TileType::Empty;
+        }
+        sim.grid.write().get_mut(Position::new(3, 3)).tile_type = TileType::Empty;
+        let rich = rich.unwrap();
+
+        sim.run_until(1).unwrap();
+        let grid = sim.grid.read();
+        assert_eq!(grid.occupant(Position::new(3, 3)), Some(rich));
+        assert_eq!(grid.iter().filter(|(pos, _)| grid.is_occupied(*pos)).count(), 2);
+    }
+
+    #[test]
+    fn test_walls_stop_movement() {
+        let run = |topology| {
+            let mut config = JobConfig::default();
+            config.world_config.width = 8;
+            config.world_config.height = 8;
+            config.world_config.hazard_damage = 0;
+            config.world_config.topology = topology;
+            let mut sim =
+                Simulation::new(config, vec![(LineageId::new(), create_mover_genome(1))]).unwrap();
+
+            let organism = sim.organisms.values_mut().next().unwrap();
+            let mut grid = sim.grid.write();
+            grid.vacate(organism.position);
+            organism.position = Position::new(6, 3);
+            grid.place(organism.position, organism.id);
+            for x in 0..8 {
+                grid.set(Position::new(x, 3), evo_core::Tile::empty());
+            }
+            drop(grid);
+
+            sim.run_until(3).unwrap();
+            sim.organisms.values().next().unwrap().position
+        };
+
+        assert_eq!(run(evo_core::Topology::Box), Position::new(7, 3));
+        assert_eq!(run(evo_core::Topology::Torus), Position::new(1, 3));
+    }
+
+    #[test]
+    fn test_regions_report_lineages_and_migrants() {
+        let run = |migration_interval| {
+            let mut config = JobConfig {
+                num_ticks: 10,
+                ..Default::default()
+            };
+            config.world_config.width = 20;
+            config.world_config.height = 10;
+            config.world_config.hazard_damage = 0;
+            config.world_config.regions.count = 2;
+            config.world_config.regions.migration_interval = migration_interval;
+            config.world_config.regions.migration_rate = 0.5;
+            let founders: Vec<_> = (0..8)
+                .map(|_| (LineageId::new(), create_test_genome()))
+                .collect();
+            let lineages: Vec<_> = founders.iter().map(|(lineage, _)| *lineage).collect();
+            let result = Simulation::new(config, founders).unwrap().run().unwrap();
+            (lineages, result)
+        };
+
+        // Without migration founders stay in the region they started in
+        let (lineages, result) = run(0);
+        assert_eq!(result.region_stats.len(), 2);
+        for (index, lineage) in lineages.iter().enumerate() {
+            assert!(result.region_stats[index % 2].lineage_stats.contains_key(lineage));
+        }
+        assert_eq!(result.region_stats[0].immigrants, 0);
+
+        let (_, result) = run(5);
+        let survivors: usize = result
+            .region_stats
+            .iter()
+            .flat_map(|region| region.lineage_stats.values())
+            .map(Vec::len)
+            .sum();
+        assert_eq!(survivors, result.survivors.len());
+        // Ring migration between two regions swaps migrants both ways
+        for (region, other) in [(0, 1), (1, 0)] {
+            assert!(result.region_stats[region].emigrants > 0);
+            assert_eq!(
+                result.region_stats[region].emigrants,
+                result.region_stats[other].immigrants
+            );
+        }
+    }
+
+    #[test]
+    fn test_mutual_mating_produces_offspring() {
+        // Propose to the east and west neighbors every tick
+        let mut genome = create_test_genome();
+        let step = &mut genome.functions[1];
+        step.num_locals = 2;
+        step.get_block_mut(0).unwrap().instructions = vec![
+            Instruction::load_const(Register(0), Value::Int(2)),
+            Instruction::new(Opcode::Mate)
+                .with_operand(Operand::Register(Register(0)))
+                .with_dest(Register(1)),
+            Instruction::load_const(Register(0), Value::Int(3)),
+            Instruction::new(Opcode::Mate)
+                .with_operand(Operand::Register(Register(0)))
+                .with_dest(Register(1)),
+            Instruction::return_value(Register(1)),
+        ];
+
+        let run = |allow_mating: bool| {
+            let mut config = JobConfig::default();
+            config.dynamic_rules.allow_mating = allow_mating;
+            config.world_config.map = Some(evo_core::MapSource::Inline(
+                "#####\n#SS.#\n#####\n".to_string(),
+            ));
+            let genomes = vec![
+                (LineageId::new(), genome.clone()),
+                (LineageId::new(), genome.clone()),
+            ];
+            let mut sim = Simulation::new(config, genomes).unwrap();
+            sim.run_until(1).unwrap();
+            sim
+        };
+
+        let sim = run(true);
+        assert_eq!(sim.births.len(), 1);
+        let birth = &sim.births[0];
+        assert_eq!(birth.parent_ids.len(), 2);
+
+        let offspring = &sim.organisms[&birth.organism_id];
+        assert_eq!(offspring.position, Position::new(3, 1));
+        let parents_energy: i32 =
+            birth.parent_ids.iter().map(|id| sim.organisms[id].energy).sum();
+        // Each parent gave a quarter of what it had left after the cost
+        assert!((offspring.energy - parents_energy / 3).abs() <= 2);
+
+        assert!(run(false).births.is_empty());
+    }
+
+    #[test]
+    fn test_share_energy_with_kin() {
+        // Give 100 energy to the east neighbor every tick
+        let mut genome = create_test_genome();
+        let step = &mut genome.functions[1];
+        step.num_locals = 1;
+        step.get_block_mut(0).unwrap().instructions = vec![
+            Instruction::new(Opcode::ShareEnergy)
+                .with_operand(Operand::Immediate(Value::Int(2)))
+                .with_operand(Operand::Immediate(Value::Int(100)))
+                .with_dest(Register(0)),
+            Instruction::return_value(Register(0)),
+        ];
+
+        let mut config = JobConfig::default();
+        config.world_config.map = Some(evo_core::MapSource::Inline(
+            "#####\n#SS.#\n#####\n".to_string(),
+        ));
+        let lineage = LineageId::new();
+        let genomes = vec![(lineage, genome.clone()), (lineage, genome)];
+        let mut sim = Simulation::new(config, genomes).unwrap();
+
+        let at = |sim: &Simulation, x| sim.grid.read().occupant(Position::new(x, 1)).unwrap();
+        let (giver, receiver) = (at(&sim, 1), at(&sim, 2));
+        assert_eq!(sim.neighbor_codes(giver)[2], NEIGHBOR_KIN);
+        assert_eq!(sim.neighbor_codes(receiver)[2], NEIGHBOR_NONE);
+
+        sim.run_until(1).unwrap();
+
+        let given = &sim.organisms[&giver].metrics;
+        assert_eq!((given.energy_given, given.kin_energy_given), (100, 100));
+        // 10% is lost in transfer
+        let received = &sim.organisms[&receiver].metrics;
+        assert_eq!((received.energy_received, received.kin_energy_received), (90, 90));
+    }
+
     #[test]
     fn test_simulation_creation() {
         let config = JobConfig {
@@ -0,0 +1,142 @@
+//! Serializable simulation snapshots for pausing and resuming islands.
+
+use crate::behavior::BehaviorTrace;
+use crate::grid::Grid;
+use crate::organism::Organism;
+use crate::simulation::BirthRecord;
+use evo_core::{Error, FitnessMetrics, JobConfig, LineageId, OrganismId, Position, Result};
+use evo_ir::Program;
+use evo_runtime::InstanceSnapshot;
+use rand_chacha::ChaCha8Rng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+/// Current snapshot format version
+pub const SNAPSHOT_VERSION: u32 = 8;
+
+/// Position of a ChaCha RNG in its stream
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RngState {
+    pub seed: [u8; 32],
+    pub stream: u64,
+    pub word_pos: u128,
+}
+
+impl From<&ChaCha8Rng> for RngState {
+    fn from(rng: &ChaCha8Rng) -> Self {
+        Self {
+            seed: rng.get_seed(),
+            stream: rng.get_stream(),
+            word_pos: rng.get_word_pos(),
+        }
+    }
+}
+
+impl RngState {
+    pub fn to_rng(&self) -> ChaCha8Rng {
+        let mut rng = ChaCha8Rng::from_seed(self.seed);
+        rng.set_stream(self.stream);
+        rng.set_word_pos(self.word_pos);
+        rng
+    }
+}
+
+/// Complete organism state, including its WASM instance if it has one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganismSnapshot {
+    pub id: OrganismId,
+    pub lineage_id: LineageId,
+    pub parent_ids: Vec<OrganismId>,
+    pub generation: u32,
+    pub position: Position,
+    pub energy: i32,
+    pub age: u64,
+    pub birth_tick: u64,
+    pub genome: Program,
+    pub metrics: FitnessMetrics,
+    pub visited_tiles: Vec<Position>,
+    pub behavior: BehaviorTrace,
+    /// `None` if the organism has not been instantiated yet
+    pub instance: Option<InstanceSnapshot>,
+}
+
+impl OrganismSnapshot {
+    pub fn capture(organism: &mut Organism) -> Self {
+        let mut visited_tiles: Vec<Position> = organism.visited_tiles.iter().copied().collect();
+        visited_tiles.sort_by_key(|p| (p.y, p.x));
+
+        Self {
+            id: organism.id,
+            lineage_id: organism.lineage_id,
+            parent_ids: organism.parent_ids.clone(),
+            generation: organism.generation,
+            position: organism.position,
+            energy: organism.energy,
+            age: organism.age,
+            birth_tick: organism.birth_tick,
+            genome: organism.genome.clone(),
+            metrics: organism.metrics.clone(),
+            visited_tiles,
+            behavior: organism.behavior.clone(),
+            instance: organism.instance.as_mut().map(|i| i.snapshot()),
+        }
+    }
+
+    /// Rebuild the organism without its instance (the simulation
+    /// re-instantiates it from the genome and restores the instance state)
+    pub fn to_organism(&self) -> Organism {
+        let mut organism = Organism::new_with_birth_tick(
+            self.lineage_id,
+            self.position,
+            self.energy,
+            self.genome.clone(),
+            self.birth_tick,
+        );
+        organism.id = self.id;
+        organism.parent_ids = self.parent_ids.clone();
+        organism.generation = self.generation;
+        organism.age = self.age;
+        organism.metrics = self.metrics.clone();
+        organism.visited_tiles = self.visited_tiles.iter().copied().collect();
+        organism.behavior = self.behavior.clone();
+        organism
+    }
+}
+
+/// Everything needed to resume a simulation exactly where it stopped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationSnapshot {
+    pub version: u32,
+    pub config: JobConfig,
+    /// Last tick that was run
+    pub tick: u64,
+    /// Tick the resumed simulation runs next
+    pub next_tick: u64,
+    pub grid: Grid,
+    /// Organisms sorted by id
+    pub organisms: Vec<OrganismSnapshot>,
+    pub rng: RngState,
+    pub births: Vec<BirthRecord>,
+    pub reproduction_attempts: u64,
+    pub reproduction_successes: u64,
+    pub total_offspring_born: u64,
+    /// Immigrants and emigrants so far, per region
+    pub migration_counts: Vec<(u64, u64)>,
+}
+
+impl SimulationSnapshot {
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let snapshot: Self = bincode::deserialize(bytes)?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(Error::Validation(format!(
+                "Unsupported snapshot version {} (expected {})",
+                snapshot.version, SNAPSHOT_VERSION
+            )));
+        }
+        Ok(snapshot)
+    }
+}
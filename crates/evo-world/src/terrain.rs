@@ -0,0 +1,622 @@
+//! Procedural terrain generation.
+//!
+//! Tile layers are laid out from score fields: every free tile gets a score
+//! from the layer's layout (random, value noise, distance to a cluster
+//! center) and the highest scoring tiles are filled until the layer's
+//! density is reached. Walls and mazes draw obstacles directly. Score fields
+//! follow the world's topology: clusters and noise only continue across
+//! edges that wrap.
+
+use crate::grid::Grid;
+use evo_core::{
+    ObstacleLayout, Position, RadiationConfig, Tile, TileLayout, TileType, Topology, WorldConfig,
+};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+/// Generate a grid using the layouts in `config.terrain`
+pub fn generate(config: &WorldConfig, rng: &mut ChaCha8Rng) -> Grid {
+    let mut terrain = Terrain::new(config.width, config.height, config.topology);
+
+    terrain.obstacle_layer(config, rng);
+    terrain.fill_layer(
+        &config.terrain.hazards,
+        config.hazard_density,
+        Tile::hazard(),
+        rng,
+    );
+    terrain.fill_layer(
+        &config.terrain.resources,
+        config.resource_density,
+        Tile::resource(config.max_resource_per_tile, config.max_resource_per_tile),
+        rng,
+    );
+
+    terrain.grid
+}
+
+/// Lay out the obstacles of an existing grid again.
+///
+/// Old obstacles become empty; new ones replace whatever tile they land on,
+/// except on occupied tiles and where `keep_clear` holds. Resource and hazard
+/// tiles under a new obstacle are lost for good: the next relayout turns the
+/// obstacle into an empty tile, not back into what was there.
+pub fn relayout_obstacles(
+    grid: &mut Grid,
+    config: &WorldConfig,
+    keep_clear: impl Fn(Position) -> bool,
+    rng: &mut ChaCha8Rng,
+) {
+    let mut mask = Terrain::new(grid.width, grid.height, grid.topology);
+    mask.obstacle_layer(config, rng);
+
+    for (pos, new_tile) in mask.grid.iter() {
+        let clear = keep_clear(pos) || grid.is_occupied(pos);
+        let tile = grid.get_mut(pos);
+        if new_tile.tile_type == TileType::Obstacle && !clear {
+            *tile = Tile::obstacle();
+        } else if tile.tile_type == TileType::Obstacle {
+            *tile = Tile::empty();
+        }
+    }
+}
+
+/// Lay out the resource tiles of an existing grid again.
+///
+/// Old resource tiles become empty and new ones are placed on empty tiles,
+/// full. Meat stays where it lies.
+pub fn relayout_resources(grid: &mut Grid, config: &WorldConfig, rng: &mut ChaCha8Rng) {
+    let mut terrain = Terrain::new(grid.width, grid.height, grid.topology);
+    for (index, (_, tile)) in grid.iter().enumerate() {
+        terrain.taken[index] = !matches!(tile.tile_type, TileType::Empty | TileType::Resource);
+    }
+    terrain.fill_layer(
+        &config.terrain.resources,
+        config.resource_density,
+        Tile::resource(config.max_resource_per_tile, config.max_resource_per_tile),
+        rng,
+    );
+
+    for (pos, new_tile) in terrain.grid.iter() {
+        let tile = grid.get_mut(pos);
+        if new_tile.tile_type == TileType::Resource {
+            *tile = Tile {
+                meat_amount: tile.meat_amount,
+                ..new_tile.clone()
+            };
+        } else if tile.tile_type == TileType::Resource {
+            *tile = Tile {
+                meat_amount: tile.meat_amount,
+                ..Tile::empty()
+            };
+        }
+    }
+}
+
+/// Mark radioactive tiles on top of whatever they are
+pub fn add_radiation(grid: &mut Grid, config: &RadiationConfig, rng: &mut ChaCha8Rng) {
+    let target = (config.density.clamp(0.0, 1.0) * (grid.width * grid.height) as f32).round() as usize;
+    if target == 0 {
+        return;
+    }
+
+    let scores = score_field(&config.layout, grid.width, grid.height, grid.topology, rng);
+    let mut candidates: Vec<usize> = (0..scores.len()).filter(|&i| scores[i] > 0.0).collect();
+    candidates.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+
+    for index in candidates.into_iter().take(target) {
+        let pos = grid.index_to_pos(index);
+        grid.get_mut(pos).radiation = config.level;
+    }
+}
+
+struct Terrain {
+    grid: Grid,
+    taken: Vec<bool>,
+}
+
+impl Terrain {
+    fn new(width: i32, height: i32, topology: Topology) -> Self {
+        let mut grid = Grid::new(width, height);
+        grid.topology = topology;
+        Self {
+            grid,
+            taken: vec![false; (width * height) as usize],
+        }
+    }
+
+    fn obstacle_layer(&mut self, config: &WorldConfig, rng: &mut ChaCha8Rng) {
+        match &config.terrain.obstacles {
+            ObstacleLayout::Uniform => self.fill_layer(
+                &TileLayout::Uniform,
+                config.obstacle_density,
+                Tile::obstacle(),
+                rng,
+            ),
+            ObstacleLayout::Noise { scale, octaves } => self.fill_layer(
+                &TileLayout::Noise {
+                    scale: *scale,
+                    octaves: *octaves,
+                },
+                config.obstacle_density,
+                Tile::obstacle(),
+                rng,
+            ),
+            ObstacleLayout::Clusters { count, radius } => self.fill_layer(
+                &TileLayout::Clusters {
+                    count: *count,
+                    radius: *radius,
+                },
+                config.obstacle_density,
+                Tile::obstacle(),
+                rng,
+            ),
+            ObstacleLayout::Walls {
+                count,
+                min_length,
+                max_length,
+            } => self.draw_walls(*count, *min_length, *max_length, rng),
+            ObstacleLayout::Maze { corridor_width } => self.carve_maze(*corridor_width, rng),
+        }
+    }
+
+    fn width(&self) -> i32 {
+        self.grid.width
+    }
+
+    fn height(&self) -> i32 {
+        self.grid.height
+    }
+
+    fn index(&self, pos: Position) -> usize {
+        let pos = pos.wrap(self.width(), self.height());
+        (pos.y * self.width() + pos.x) as usize
+    }
+
+    fn place(&mut self, pos: Position, tile: Tile) {
+        let index = self.index(pos);
+        self.taken[index] = true;
+        self.grid.set(pos, tile);
+    }
+
+    /// Fill the best scoring free tiles until `density` of the map is covered
+    fn fill_layer(&mut self, layout: &TileLayout, density: f32, tile: Tile, rng: &mut ChaCha8Rng) {
+        let target = (density.clamp(0.0, 1.0) * self.taken.len() as f32).round() as usize;
+        if target == 0 {
+            return;
+        }
+
+        let scores = score_field(layout, self.width(), self.height(), self.grid.topology, rng);
+        let mut candidates: Vec<usize> = (0..scores.len())
+            .filter(|&i| !self.taken[i] && scores[i] > 0.0)
+            .collect();
+        candidates.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+
+        for index in candidates.into_iter().take(target) {
+            let pos = self.grid.index_to_pos(index);
+            self.place(pos, tile.clone());
+        }
+    }
+
+    /// Random horizontal and vertical wall segments
+    fn draw_walls(&mut self, count: u32, min_length: i32, max_length: i32, rng: &mut ChaCha8Rng) {
+        let min_length = min_length.max(1);
+        let max_length = max_length.max(min_length);
+
+        for _ in 0..count {
+            let start = Position::new(
+                rng.gen_range(0..self.width()),
+                rng.gen_range(0..self.height()),
+            );
+            let length = rng.gen_range(min_length..=max_length);
+            let (dx, dy) = if rng.gen_bool(0.5) { (1, 0) } else { (0, 1) };
+
+            for step in 0..length {
+                self.place(start.add(dx * step, dy * step), Tile::obstacle());
+            }
+        }
+    }
+
+    /// Carve a perfect maze with a randomized depth-first search.
+    ///
+    /// Cells are `corridor_width` tiles wide with one-tile walls between
+    /// them; tiles that don't fit a whole cell on the right and bottom edges
+    /// stay solid.
+    fn carve_maze(&mut self, corridor_width: i32, rng: &mut ChaCha8Rng) {
+        let corridor = corridor_width.max(1);
+        let stride = corridor + 1;
+        let cells_x = (self.width() - 1) / stride;
+        let cells_y = (self.height() - 1) / stride;
+
+        for index in 0..self.taken.len() {
+            let pos = self.grid.index_to_pos(index);
+            self.place(pos, Tile::obstacle());
+        }
+        if cells_x == 0 || cells_y == 0 {
+            return;
+        }
+
+        let open = |terrain: &mut Terrain, x0: i32, y0: i32, w: i32, h: i32| {
+            for y in y0..y0 + h {
+                for x in x0..x0 + w {
+                    let pos = Position::new(x, y);
+                    let index = terrain.index(pos);
+                    terrain.taken[index] = true;
+                    terrain.grid.set(pos, Tile::empty());
+                }
+            }
+        };
+        let origin = |cell: i32| cell * stride + 1;
+
+        let mut visited = vec![false; (cells_x * cells_y) as usize];
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        open(self, origin(0), origin(0), corridor, corridor);
+
+        while let Some(&(cx, cy)) = stack.last() {
+            let mut neighbors: Vec<(i32, i32)> = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .iter()
+                .map(|(dx, dy)| (cx + dx, cy + dy))
+                .filter(|&(nx, ny)| {
+                    nx >= 0
+                        && ny >= 0
+                        && nx < cells_x
+                        && ny < cells_y
+                        && !visited[(ny * cells_x + nx) as usize]
+                })
+                .collect();
+
+            if neighbors.is_empty() {
+                stack.pop();
+                continue;
+            }
+            neighbors.shuffle(rng);
+            let (nx, ny) = neighbors[0];
+            visited[(ny * cells_x + nx) as usize] = true;
+
+            // Open the next cell and the wall between the two cells
+            open(self, origin(nx), origin(ny), corridor, corridor);
+            let (x0, y0) = (origin(cx.min(nx)), origin(cy.min(ny)));
+            if nx != cx {
+                open(self, x0 + corridor, y0, 1, corridor);
+            } else {
+                open(self, x0, y0 + corridor, corridor, 1);
+            }
+
+            stack.push((nx, ny));
+        }
+    }
+}
+
+/// Score every tile for a layout; tiles scoring zero or less are never filled
+fn score_field(
+    layout: &TileLayout,
+    width: i32,
+    height: i32,
+    topology: Topology,
+    rng: &mut ChaCha8Rng,
+) -> Vec<f32> {
+    let size = (width * height) as usize;
+
+    match layout {
+        TileLayout::Uniform => (0..size).map(|_| rng.gen::<f32>() + f32::EPSILON).collect(),
+        TileLayout::Noise { scale, octaves } => {
+            let noise = ValueNoise::new(width, height, topology, *scale, *octaves, rng);
+            (0..size)
+                .map(|i| {
+                    let x = i as i32 % width;
+                    let y = i as i32 / width;
+                    noise.sample(x, y) + f32::EPSILON
+                })
+                .collect()
+        }
+        TileLayout::Clusters { count, radius } => {
+            let radius = radius.max(0.5);
+            let centers: Vec<(i32, i32)> = (0..*count)
+                .map(|_| (rng.gen_range(0..width), rng.gen_range(0..height)))
+                .collect();
+
+            (0..size)
+                .map(|i| {
+                    let x = i as i32 % width;
+                    let y = i as i32 / width;
+                    let closeness = centers
+                        .iter()
+                        .map(|&(cx, cy)| {
+                            let center = Position::new(cx, cy);
+                            let distance =
+                                layout_distance(topology, Position::new(x, y), center, width, height);
+                            1.0 - distance / radius
+                        })
+                        .fold(f32::MIN, f32::max);
+
+                    // Jitter inside the patch gives ragged, natural edges
+                    if closeness > 0.0 {
+                        closeness + rng.gen::<f32>() * 0.25
+                    } else {
+                        0.0
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+/// Whether the x and y axes wrap around
+fn wrapping_axes(topology: Topology) -> (bool, bool) {
+    match topology {
+        Topology::Torus | Topology::Hex => (true, true),
+        Topology::Cylinder => (true, false),
+        Topology::Box => (false, false),
+    }
+}
+
+/// Distance between two tiles, across the edges only where they wrap:
+/// Euclidean on square grids, in steps on hex grids
+fn layout_distance(topology: Topology, from: Position, to: Position, width: i32, height: i32) -> f32 {
+    let (wrap_x, wrap_y) = wrapping_axes(topology);
+    if topology == Topology::Hex {
+        // The nearest copy of `to` on the wrapped plane; heights are even,
+        // so shifted copies keep their row parity
+        let copies = |size: i32, wraps: bool| if wraps { vec![-size, 0, size] } else { vec![0] };
+        return copies(width, wrap_x)
+            .into_iter()
+            .flat_map(|dx| copies(height, wrap_y).into_iter().map(move |dy| to.add(dx, dy)))
+            .map(|copy| topology.distance(from, copy))
+            .min()
+            .unwrap_or(0) as f32;
+    }
+
+    let axis = |a: i32, b: i32, size: i32, wraps: bool| {
+        let d = (a - b).abs();
+        if wraps {
+            d.min(size - d) as f32
+        } else {
+            d as f32
+        }
+    };
+    let dx = axis(from.x, to.x, width, wrap_x);
+    let dy = axis(from.y, to.y, height, wrap_y);
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Fractal value noise; it tiles seamlessly along the axes that wrap
+struct ValueNoise {
+    width: i32,
+    height: i32,
+    octaves: Vec<NoiseOctave>,
+}
+
+struct NoiseOctave {
+    period_x: usize,
+    period_y: usize,
+    /// Lattice columns and rows: one more than the period along an axis
+    /// that doesn't wrap, so its far edge isn't tied to the near one
+    columns: usize,
+    rows: usize,
+    amplitude: f32,
+    lattice: Vec<f32>,
+}
+
+impl ValueNoise {
+    fn new(
+        width: i32,
+        height: i32,
+        topology: Topology,
+        scale: f32,
+        octaves: u32,
+        rng: &mut ChaCha8Rng,
+    ) -> Self {
+        let scale = scale.max(1.0);
+        let (wrap_x, wrap_y) = wrapping_axes(topology);
+        let octaves = (0..octaves.max(1))
+            .map(|octave| {
+                let feature = scale / (1 << octave) as f32;
+                let period_x = ((width as f32 / feature).round() as usize).max(1);
+                let period_y = ((height as f32 / feature).round() as usize).max(1);
+                let columns = period_x + usize::from(!wrap_x);
+                let rows = period_y + usize::from(!wrap_y);
+                NoiseOctave {
+                    period_x,
+                    period_y,
+                    columns,
+                    rows,
+                    amplitude: 0.5f32.powi(octave as i32),
+                    lattice: (0..columns * rows).map(|_| rng.gen::<f32>()).collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            width,
+            height,
+            octaves,
+        }
+    }
+
+    fn sample(&self, x: i32, y: i32) -> f32 {
+        self.octaves
+            .iter()
+            .map(|octave| {
+                let fx = x as f32 * octave.period_x as f32 / self.width as f32;
+                let fy = y as f32 * octave.period_y as f32 / self.height as f32;
+                let (x0, y0) = (fx.floor() as usize, fy.floor() as usize);
+                let (tx, ty) = (smoothstep(fx.fract()), smoothstep(fy.fract()));
+
+                let at = |lx: usize, ly: usize| {
+                    octave.lattice[(ly % octave.rows) * octave.columns + (lx % octave.columns)]
+                };
+                let top = lerp(at(x0, y0), at(x0 + 1, y0), tx);
+                let bottom = lerp(at(x0, y0 + 1), at(x0 + 1, y0 + 1), tx);
+                lerp(top, bottom, ty) * octave.amplitude
+            })
+            .sum()
+    }
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evo_core::TerrainConfig;
+    use rand::SeedableRng;
+    use std::collections::VecDeque;
+
+    fn config(terrain: TerrainConfig) -> WorldConfig {
+        WorldConfig {
+            width: 48,
+            height: 32,
+            terrain,
+            ..Default::default()
+        }
+    }
+
+    fn count(grid: &Grid, tile_type: TileType) -> usize {
+        grid.iter().filter(|(_, t)| t.tile_type == tile_type).count()
+    }
+
+    #[test]
+    fn test_noise_layers_match_density() {
+        let config = WorldConfig {
+            resource_density: 0.25,
+            obstacle_density: 0.1,
+            ..config(TerrainConfig {
+                obstacles: ObstacleLayout::Noise {
+                    scale: 12.0,
+                    octaves: 3,
+                },
+                hazards: TileLayout::Clusters {
+                    count: 3,
+                    radius: 4.0,
+                },
+                resources: TileLayout::Noise {
+                    scale: 8.0,
+                    octaves: 2,
+                },
+            })
+        };
+
+        let grid = generate(&config, &mut ChaCha8Rng::seed_from_u64(1));
+        let tiles = (config.width * config.height) as f32;
+
+        assert_eq!(count(&grid, TileType::Obstacle), (0.1 * tiles).round() as usize);
+        assert_eq!(count(&grid, TileType::Resource), (0.25 * tiles).round() as usize);
+        assert!(count(&grid, TileType::Hazard) > 0);
+
+        // Same seed, same map
+        let again = generate(&config, &mut ChaCha8Rng::seed_from_u64(1));
+        for ((_, a), (_, b)) in grid.iter().zip(again.iter()) {
+            assert_eq!(a.tile_type, b.tile_type);
+        }
+    }
+
+    #[test]
+    fn test_clusters_stay_within_radius() {
+        let config = WorldConfig {
+            resource_density: 1.0,
+            obstacle_density: 0.0,
+            hazard_density: 0.0,
+            ..config(TerrainConfig {
+                resources: TileLayout::Clusters {
+                    count: 1,
+                    radius: 5.0,
+                },
+                ..Default::default()
+            })
+        };
+
+        let grid = generate(&config, &mut ChaCha8Rng::seed_from_u64(2));
+        let resources = count(&grid, TileType::Resource);
+
+        // Only the patch is filled, however high the density
+        assert!(resources > 0);
+        assert!(resources as f32 <= std::f32::consts::PI * 6.0 * 6.0);
+    }
+
+    #[test]
+    fn test_layouts_follow_topology() {
+        let (corner, right, bottom) = (Position::new(0, 0), Position::new(47, 0), Position::new(0, 31));
+        let distance = |topology, to| layout_distance(topology, corner, to, 48, 32);
+
+        assert_eq!(distance(Topology::Torus, right), 1.0);
+        assert_eq!(distance(Topology::Torus, bottom), 1.0);
+        // Clusters don't reach across walls
+        assert_eq!(distance(Topology::Box, right), 47.0);
+        assert_eq!(distance(Topology::Cylinder, right), 1.0);
+        assert_eq!(distance(Topology::Cylinder, bottom), 31.0);
+        assert_eq!(distance(Topology::Hex, right), 1.0);
+        assert_eq!(distance(Topology::Hex, Position::new(3, 0)), 3.0);
+
+        // Noise only tiles along the axes that wrap
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let noise = ValueNoise::new(48, 32, Topology::Cylinder, 12.0, 1, &mut rng);
+        let octave = &noise.octaves[0];
+        assert_eq!((octave.period_x, octave.period_y), (4, 3));
+        assert_eq!((octave.columns, octave.rows), (4, 4));
+    }
+
+    #[test]
+    fn test_radiation_overlays_tiles() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let mut grid = Grid::new(20, 20);
+        grid.set(Position::new(0, 0), Tile::obstacle());
+
+        let radiation = RadiationConfig {
+            density: 0.1,
+            layout: TileLayout::Uniform,
+            ..Default::default()
+        };
+        add_radiation(&mut grid, &radiation, &mut rng);
+
+        let irradiated: Vec<&Tile> = grid
+            .iter()
+            .map(|(_, t)| t)
+            .filter(|t| t.radiation > 0.0)
+            .collect();
+        assert_eq!(irradiated.len(), 40);
+        assert!(irradiated.iter().all(|t| t.radiation == radiation.level));
+        assert_eq!(count(&grid, TileType::Obstacle), 1);
+    }
+
+    #[test]
+    fn test_maze_is_connected() {
+        let config = WorldConfig {
+            hazard_density: 0.0,
+            resource_density: 0.0,
+            ..config(TerrainConfig {
+                obstacles: ObstacleLayout::Maze { corridor_width: 2 },
+                ..Default::default()
+            })
+        };
+        let grid = generate(&config, &mut ChaCha8Rng::seed_from_u64(3));
+
+        let open: Vec<Position> = grid
+            .iter()
+            .filter(|(_, t)| t.tile_type != TileType::Obstacle)
+            .map(|(p, _)| p)
+            .collect();
+        assert!(!open.is_empty());
+
+        let mut seen = std::collections::HashSet::new();
+        let mut queue = VecDeque::from([open[0]]);
+        seen.insert(open[0]);
+        while let Some(pos) = queue.pop_front() {
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let next = pos.add(dx, dy).wrap(grid.width, grid.height);
+                if grid.get(next).tile_type != TileType::Obstacle && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        assert_eq!(seen.len(), open.len());
+    }
+}